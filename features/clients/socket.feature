@@ -0,0 +1,82 @@
+Feature: send triggers through the socket
+
+  Background:
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo running all tests"
+          }
+        ]
+      }
+      """
+    And Contest is running
+
+  Scenario: valid trigger
+    When a client sends '{ "command": "test-all" }' through the socket
+    Then the client receives
+      """
+      {"status":"accepted","command":"echo running all tests"}
+      {"status":"finished","outcome":"pass"}
+      """
+    And it prints
+      """
+      executing: echo running all tests
+      running all tests
+      SUCCESS
+      """
+
+  Scenario: failing test
+    When a client sends '{ "command": "custom-command", "run": "false" }' through the socket
+    Then the client receives
+      """
+      {"status":"accepted","command":"false"}
+      {"status":"finished","outcome":"fail"}
+      """
+
+  Scenario: trigger without matching action
+    When a client sends '{ "command": "test-file", "file": "foo.rs" }' through the socket
+    Then the client receives
+      """
+      {"status":"rejected","error":"cannot determine command for trigger: test-file foo.rs","guidance":"Please make sure that this action is listed in contest.json"}
+      """
+    # the connection remains open for more triggers
+    When a client sends '{ "command": "test-all" }' through the socket
+    Then the client receives
+      """
+      {"status":"accepted","command":"echo running all tests"}
+      {"status":"finished","outcome":"pass"}
+      """
+
+  Scenario: malformed trigger
+    When a client sends '{ "command": "test-file-line", "file": "foo.rs" }' through the socket
+    Then the client receives
+      """
+      {"status":"rejected","error":"invalid trigger received: { \"command\": \"test-file-line\", \"file\": \"foo.rs\" }","guidance":"missing \"line\" field"}
+      """
+    # the server keeps running and executes the next trigger
+    When a client sends '{ "command": "test-all" }' through the socket
+    Then the client receives
+      """
+      {"status":"accepted","command":"echo running all tests"}
+      {"status":"finished","outcome":"pass"}
+      """
+    And it prints
+      """
+      Error: invalid trigger received: { "command": "test-file-line", "file": "foo.rs" }
+      missing "line" field
+      executing: echo running all tests
+      running all tests
+      SUCCESS
+      """
+
+  Scenario: the FIFO keeps working
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: echo running all tests
+      running all tests
+      SUCCESS
+      """
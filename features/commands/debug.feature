@@ -23,6 +23,7 @@ Feature: run in debug mode
       """
     And it prints
       """
+      using configuration file contest.json
       Contest is online in debug mode, Ctrl-C to exit
       """
 
@@ -0,0 +1,72 @@
+Feature: explaining how a trigger resolves
+
+  Background:
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo running all tests"
+          },
+          {
+            "type": "test-file-line",
+            "files": "**/*.rs",
+            "vars": [
+              {
+                "name": "fn_name",
+                "source": "currentOrAboveLineContent",
+                "filter": "\\bfn (\\w+)\\("
+              }
+            ],
+            "run": "echo cargo test {{fn_name}}"
+          }
+        ]
+      }
+      """
+    And file "foo.rs" with content
+      """
+      // This is the source code file that the user has currently opened.
+
+      pub fn my_func() {
+        println!("This is a very simple function.");
+      }
+      """
+
+  Scenario: matching trigger
+    When I run 'contest explain "{ \"command\": \"test-file-line\", \"file\": \"foo.rs\", \"line\": 4 }"'
+    Then it prints
+      """
+      using configuration file contest.json
+      trigger: test-file-line foo.rs:4
+      actions[0] test-all: no match
+      actions[1] test-file-line **/*.rs: match
+      file = foo.rs
+      """
+    And it exits with output containing
+      """
+        file_dir = .
+        file_ext = rs
+        file_module_colons = foo
+        file_module_dots = foo
+        file_name = foo.rs
+        file_rel_to_config = foo.rs
+        file_stem = foo
+        line = 4
+        fn_name = my_func (found on line 4)
+      command: echo cargo test my_func
+      """
+
+  Scenario: trigger without matching action
+    When I run 'contest explain "{ \"command\": \"test-file\", \"file\": \"foo.rs\" }"'
+    Then it fails with this output
+      """
+      using configuration file contest.json
+      trigger: test-file foo.rs
+      actions[0] test-all: no match
+      actions[1] test-file-line **/*.rs: no match
+
+      Error: cannot determine command for trigger: test-file foo.rs
+
+      Please make sure that this action is listed in contest.json
+      """
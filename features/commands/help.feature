@@ -9,11 +9,15 @@ Feature: display help
       Usage: contest [COMMAND]
 
       Commands:
-        debug  Print the received triggers from the pipe
-        run    Run the given client-side trigger and exit
-        init   Create an example configuration file
-        start  Execute the received triggers from the pipe
-        help   Print this message or the help of the given subcommand(s)
+        debug     Print the received triggers from the pipe
+        run       Run the given client-side trigger and exit
+        history   List the past test runs, or show the output of the given one
+        init      Create an example configuration file
+        explain   Show how the given client-side trigger resolves into a command without running it
+        start     Execute the received triggers from the pipe
+        validate  Report all problems in the configuration file
+        watch     Execute the received triggers and test files when they change
+        help      Print this message or the help of the given subcommand(s)
 
       Options:
         -h, --help     Print help
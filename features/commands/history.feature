@@ -0,0 +1,56 @@
+Feature: test run history
+
+  Background:
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo running all tests"
+          }
+        ],
+        "options": {
+          "history": 20
+        }
+      }
+      """
+
+  Scenario: list the test runs
+    Given Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: echo running all tests
+      running all tests
+      SUCCESS
+      """
+    When I run "contest history"
+    Then it exits with output containing
+      """
+      | pass    | test-all
+      """
+
+  Scenario: show a test run
+    Given Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: echo running all tests
+      running all tests
+      SUCCESS
+      """
+    When I run "contest history 1"
+    Then it exits with output containing
+      """
+      outcome: pass
+
+      running all tests
+      """
+
+  Scenario: no test runs
+    When I run "contest history"
+    Then it exits with this output
+      """
+      no test runs recorded yet
+      """
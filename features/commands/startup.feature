@@ -15,5 +15,6 @@ Feature: Starting Contest
     When I run "contest"
     Then it prints
       """
+      using configuration file contest.json
       Contest is online, Ctrl-C to exit
       """
@@ -0,0 +1,61 @@
+Feature: validating the configuration file
+
+  Scenario: valid configuration
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.ts",
+            "run": "echo testing file {{file}}"
+          }
+        ]
+      }
+      """
+    When I run "contest validate"
+    Then it exits with this output
+      """
+      configuration file contest.json is valid
+      """
+
+  Scenario: configuration with problems
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.ts",
+            "run": "echo testing file {{file}}"
+          },
+          {
+            "type": "test-file",
+            "files": "src/*.ts",
+            "run": "echo testing source file {{file}}",
+            "comment": "source files"
+          },
+          {
+            "type": "test-all",
+            "run": ""
+          }
+        ]
+      }
+      """
+    When I run "contest validate"
+    Then it fails with this output
+      """
+      error in actions[2]: the "run" field in your configuration file is empty
+      warning: actions[1] (source files) never runs because actions[0] always matches first
+
+      Error: the configuration file contains 1 error
+      """
+
+  Scenario: no configuration file
+    When I run "contest validate"
+    Then it fails with this output
+      """
+      Error: cannot find contest.json
+
+      Please run "contest init" to create a configuration file.
+      """
@@ -0,0 +1,122 @@
+Feature: test files when they change
+
+  Scenario: changing a file that matches a "test-file" action
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": "echo testing {{file}}"
+          }
+        ]
+      }
+      """
+    And Contest is watching
+    When file "foo.rs" changes to
+      """
+      fn foo() {}
+      """
+    Then it prints
+      """
+      executing: echo testing foo.rs
+      testing foo.rs
+      SUCCESS
+      """
+
+  Scenario: changing an ignored file
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": "echo testing {{file}}"
+          }
+        ],
+        "options": {
+          "watch": {
+            "ignore": ["ignored.rs"]
+          }
+        }
+      }
+      """
+    And Contest is watching
+    When file "ignored.rs" changes to
+      """
+      fn ignored() {}
+      """
+    And file "foo.rs" changes to
+      """
+      fn foo() {}
+      """
+    Then it prints
+      """
+      executing: echo testing foo.rs
+      testing foo.rs
+      SUCCESS
+      """
+
+  Scenario: changing a file listed in .gitignore
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": "echo testing {{file}}"
+          }
+        ]
+      }
+      """
+    And file ".gitignore" with content
+      """
+      generated.rs
+      """
+    And Contest is watching
+    When file "generated.rs" changes to
+      """
+      fn generated() {}
+      """
+    And file "foo.rs" changes to
+      """
+      fn foo() {}
+      """
+    Then it prints
+      """
+      executing: echo testing foo.rs
+      testing foo.rs
+      SUCCESS
+      """
+
+  Scenario: fall back to testing everything
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo testing everything"
+          }
+        ],
+        "options": {
+          "watch": {
+            "fallbackToTestAll": true
+          }
+        }
+      }
+      """
+    And Contest is watching
+    When file "README.md" changes to
+      """
+      # Hello
+      """
+    Then it prints
+      """
+      executing: echo testing everything
+      testing everything
+      SUCCESS
+      """
@@ -0,0 +1,42 @@
+Feature: cancel the running test when a new trigger arrives
+
+  Scenario: enabled
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [],
+        "options": {
+          "cancelOnNewTrigger": true
+        }
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "custom-command", "run": "sleep 10" }'
+    And receiving the command '{ "command": "custom-command", "run": "echo second run" }'
+    Then it prints
+      """
+      executing: sleep 10
+      superseded by a new trigger
+      executing: echo second run
+      second run
+      SUCCESS
+      """
+
+  Scenario: disabled
+    Given file "contest.json" with content
+      """
+      {
+        "actions": []
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "custom-command", "run": "sleep 0.2" }'
+    And receiving the command '{ "command": "custom-command", "run": "echo second run" }'
+    Then it prints
+      """
+      executing: sleep 0.2
+      SUCCESS
+      executing: echo second run
+      second run
+      SUCCESS
+      """
@@ -0,0 +1,35 @@
+Feature: finding the configuration file in a parent directory
+
+  Scenario: starting Contest in a subdirectory
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.test.ts",
+            "run": "cat {{file}}"
+          }
+        ]
+      }
+      """
+    And file "test/chars.test.ts" with content
+      """
+      testing chars
+      """
+    And file "sub/readme.md" with content
+      """
+      a subdirectory
+      """
+    When I run "contest" in "sub"
+    Then it prints
+      """
+      using configuration file ../contest.json
+      Contest is online, Ctrl-C to exit
+      """
+    When receiving the command '{ "command": "test-file", "file": "test/chars.test.ts" }'
+    Then it prints
+      """
+      executing: cat test/chars.test.ts
+      testing chars
+      """
@@ -0,0 +1,98 @@
+Feature: working directory and environment variables of actions
+
+  Scenario: action with working directory and environment variables
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.ts",
+            "run": "sh -c 'pwd | xargs basename && echo $TEST_FILE $LEVEL $GLOBAL'",
+            "cwd": "sub",
+            "env": {
+              "TEST_FILE": "{{file}}",
+              "LEVEL": "action"
+            }
+          }
+        ],
+        "options": {
+          "env": {
+            "LEVEL": "global",
+            "GLOBAL": "yes"
+          }
+        }
+      }
+      """
+    And file "sub/readme.md" with content
+      """
+      a subdirectory
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "foo.ts" }'
+    Then it prints
+      """
+      executing: sh -c 'pwd | xargs basename && echo $TEST_FILE $LEVEL $GLOBAL'
+      sub
+      foo.ts action yes
+      SUCCESS
+      """
+
+  Scenario: working directory does not exist
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo hello",
+            "cwd": "zonk"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it fails with this output
+      """
+      executing: echo hello
+
+      Error: the directory to run the test command in does not exist: zonk
+
+      Please verify the "cwd" field in your configuration file.
+      """
+
+  Scenario: working directory does not exist for a trigger from the socket
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo hello",
+            "cwd": "zonk"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When a client sends '{ "command": "test-all" }' through the socket
+    Then the client receives
+      """
+      {"status":"accepted","command":"echo hello"}
+      {"status":"rejected","error":"the directory to run the test command in does not exist: zonk","guidance":"Please verify the \"cwd\" field in your configuration file."}
+      """
+    And it prints
+      """
+      executing: echo hello
+      Error: the directory to run the test command in does not exist: zonk
+      Please verify the "cwd" field in your configuration file.
+      """
+    # the server keeps running and executes the next trigger
+    When a client sends '{ "command": "custom-command", "run": "echo still running" }' through the socket
+    Then it prints
+      """
+      executing: echo still running
+      still running
+      SUCCESS
+      """
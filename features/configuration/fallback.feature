@@ -0,0 +1,119 @@
+Feature: falling back to broader actions if no test is found at the cursor
+
+  Background:
+    Given file "foo.rs" with content
+      """
+      // no functions here
+      """
+
+  Scenario: falling back to the test-file action
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo testing all files"
+          },
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": "echo testing file {{file}}"
+          },
+          {
+            "type": "test-file-line",
+            "files": "**/*.rs",
+            "vars": [
+              {
+                "name": "fn_name",
+                "source": "currentOrAboveLineContent",
+                "filter": "\\bfn (\\w+)\\("
+              }
+            ],
+            "run": "echo testing function {{fn_name}}",
+            "fallback": true
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file-line", "file": "foo.rs", "line": 1 }'
+    Then it prints
+      """
+      did not find pattern \bfn (\w+)\( in file foo.rs at line 1, falling back to test-file
+      executing: echo testing file foo.rs
+      testing file foo.rs
+      SUCCESS
+      """
+
+  Scenario: explaining a fallback
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo testing all files"
+          },
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": "echo testing file {{file}}"
+          },
+          {
+            "type": "test-file-line",
+            "files": "**/*.rs",
+            "vars": [
+              {
+                "name": "fn_name",
+                "source": "currentOrAboveLineContent",
+                "filter": "\\bfn (\\w+)\\("
+              }
+            ],
+            "run": "echo testing function {{fn_name}}",
+            "fallback": true
+          }
+        ]
+      }
+      """
+    When I run 'contest explain "{ \"command\": \"test-file-line\", \"file\": \"foo.rs\", \"line\": 1 }"'
+    Then it exits with output containing
+      """
+      did not find pattern \bfn (\w+)\( in file foo.rs at line 1, falling back to test-file
+      command: echo testing file foo.rs
+      """
+
+  Scenario: falling back to the test-all action
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo testing all files"
+          },
+          {
+            "type": "test-file-line",
+            "files": "**/*.rs",
+            "vars": [
+              {
+                "name": "fn_name",
+                "source": "currentOrAboveLineContent",
+                "filter": "\\bfn (\\w+)\\("
+              }
+            ],
+            "run": "echo testing function {{fn_name}}",
+            "fallback": true
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file-line", "file": "foo.rs", "line": 1 }'
+    Then it prints
+      """
+      did not find pattern \bfn (\w+)\( in file foo.rs at line 1, falling back to test-all
+      executing: echo testing all files
+      testing all files
+      SUCCESS
+      """
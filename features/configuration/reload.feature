@@ -0,0 +1,62 @@
+Feature: reloading the configuration file while Contest is running
+
+  Background:
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo running all tests"
+          }
+        ]
+      }
+      """
+    And Contest is running
+
+  Scenario: valid change
+    When file "contest.json" changes to
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo running the new tests"
+          }
+        ]
+      }
+      """
+    Then it prints
+      """
+      reloaded the configuration file
+      """
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: echo running the new tests
+      running the new tests
+      """
+
+  Scenario: invalid change
+    When file "contest.json" changes to
+      """
+      {
+        "actions": [
+          {
+            "type": "zonk",
+            "run": "echo running the new tests"
+          }
+        ]
+      }
+      """
+    Then it prints
+      """
+      Error: Cannot parse configuration file: unknown variant `zonk`, expected one of `test-all`, `test-file`, `test-file-line`, `test-file-range`, `test-dir`
+      keeping the previous configuration
+      """
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: echo running all tests
+      running all tests
+      """
@@ -0,0 +1,61 @@
+Feature: run commands through a shell
+
+  Scenario: global shell
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo one && echo two | tr a-z A-Z"
+          }
+        ],
+        "options": {
+          "shell": "sh -c"
+        }
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: echo one && echo two | tr a-z A-Z
+      one
+      TWO
+      SUCCESS
+      """
+
+  Scenario: shell of an action
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo one && echo two",
+            "shell": "sh -c"
+          },
+          {
+            "type": "test-file",
+            "files": "**/*.ts",
+            "run": "echo one && echo {{file}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: echo one && echo two
+      one
+      two
+      SUCCESS
+      """
+    When receiving the command '{ "command": "test-file", "file": "foo.ts" }'
+    Then it prints
+      """
+      executing: echo one && echo foo.ts
+      one && echo foo.ts
+      SUCCESS
+      """
@@ -0,0 +1,143 @@
+Feature: actions with several steps
+
+  Background:
+    Given file "foo.rs" with content
+      """
+      // a test file
+      """
+
+  Scenario: all steps pass
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": ["echo linting {{file}}", "echo testing {{file}}"]
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "foo.rs" }'
+    Then it prints
+      """
+      executing: echo linting foo.rs
+      linting foo.rs
+      executing: echo testing foo.rs
+      testing foo.rs
+      step 1: pass       echo linting foo.rs
+      step 2: pass       echo testing foo.rs
+      SUCCESS
+      """
+
+  Scenario: a step fails
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": ["false", "echo testing {{file}}"]
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "foo.rs" }'
+    Then it prints
+      """
+      executing: false
+      step 1: fail       false
+      step 2: skipped    echo testing foo.rs
+      FAILED
+      """
+
+  Scenario: continuing after a failing step
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": ["false", "echo testing {{file}}"],
+            "continueOnError": true
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "foo.rs" }'
+    Then it prints
+      """
+      executing: false
+      executing: echo testing foo.rs
+      testing foo.rs
+      step 1: fail       false
+      step 2: pass       echo testing foo.rs
+      FAILED
+      """
+
+  Scenario: parallel steps with grouped output
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": ["echo backend {{file}}", "false", "echo frontend {{file}}"],
+            "parallel": true
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "foo.rs" }'
+    Then it prints
+      """
+      executing: echo backend foo.rs
+      executing: false
+      executing: echo frontend foo.rs
+      --- step 1: echo backend foo.rs ---
+      backend foo.rs
+      --- step 2: false ---
+      --- step 3: echo frontend foo.rs ---
+      frontend foo.rs
+      step 1: pass       echo backend foo.rs
+      step 2: fail       false
+      step 3: pass       echo frontend foo.rs
+      FAILED
+      """
+
+  Scenario: parallel steps with prefixed output
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "run": ["sleep 0.5 && echo backend {{file}}", "echo frontend {{file}}"],
+            "shell": "sh -c",
+            "parallel": true,
+            "parallelOutput": "prefixed"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "foo.rs" }'
+    Then it prints
+      """
+      executing: sleep 0.5 && echo backend foo.rs
+      executing: echo frontend foo.rs
+      [2] frontend foo.rs
+      [1] backend foo.rs
+      step 1: pass       sleep 0.5 && echo backend foo.rs
+      step 2: pass       echo frontend foo.rs
+      SUCCESS
+      """
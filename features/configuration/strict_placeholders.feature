@@ -0,0 +1,98 @@
+Feature: placeholders without values
+
+  Scenario: strict placeholders by default
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo {{ target }}",
+            "comment": "all tests"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      Error: no values for placeholders {{ target }} in action "all tests"
+      Please define these values in the "vars" section of the action or set "strictPlaceholders" to false in the options.
+      """
+    # the server keeps running
+    When receiving the command '{ "command": "custom-command", "run": "echo still running" }'
+    Then it prints
+      """
+      executing: echo still running
+      still running
+      SUCCESS
+      """
+
+  Scenario: disabling strict placeholders
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo {{ target }}"
+          }
+        ],
+        "options": {
+          "strictPlaceholders": false
+        }
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: echo {{ target }}
+      {{ target }}
+      SUCCESS
+      """
+
+  Scenario: validating placeholders
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo {{ file }}"
+          }
+        ]
+      }
+      """
+    When I run "contest validate"
+    Then it fails with this output
+      """
+      error in actions[0]: unknown placeholders: {{ file }}
+        Placeholders must be built into the action type or defined in the "vars" section of the action.
+
+      Error: the configuration file contains 1 error
+      """
+
+  Scenario: validating placeholders without strict placeholders
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "echo {{ target }}"
+          }
+        ],
+        "options": {
+          "strictPlaceholders": false
+        }
+      }
+      """
+    When I run "contest validate"
+    Then it exits with this output
+      """
+      warning in actions[0]: unknown placeholders: {{ target }}
+        Placeholders must be built into the action type or defined in the "vars" section of the action.
+      configuration file contest.json is valid
+      """
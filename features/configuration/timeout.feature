@@ -0,0 +1,64 @@
+Feature: stop tests that run too long
+
+  Scenario: action timeout
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "sleep 10",
+            "timeout": 0.2
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: sleep 10
+      TIMEOUT
+      """
+
+  Scenario: global timeout
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [],
+        "options": {
+          "timeout": 0.2
+        }
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "custom-command", "run": "sleep 10" }'
+    Then it prints
+      """
+      executing: sleep 10
+      TIMEOUT
+      """
+
+  Scenario: action timeout overrides the global timeout
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "sleep 0.2",
+            "timeout": 10
+          }
+        ],
+        "options": {
+          "timeout": 0.1
+        }
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      executing: sleep 0.2
+      SUCCESS
+      """
@@ -0,0 +1,45 @@
+Feature: built-in placeholders derived from the file path
+
+  Scenario: using the built-in placeholders
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.py",
+            "run": "echo {{file_name}} {{file_stem}} {{file_ext}} {{file_dir}} {{file_rel_to_config}} {{file_module_dots}} {{file_module_colons}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "tests/unit/test_parser.py" }'
+    Then it prints
+      """
+      executing: echo test_parser.py test_parser py tests/unit tests/unit/test_parser.py tests.unit.test_parser tests::unit::test_parser
+      test_parser.py test_parser py tests/unit tests/unit/test_parser.py tests.unit.test_parser tests::unit::test_parser
+      SUCCESS
+      """
+
+  Scenario: escaping literal braces
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.go",
+            "run": "echo \\{{.ImportPath}} {{file_dir}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "pkg/parser/parser_test.go" }'
+    Then it prints
+      """
+      executing: echo {{.ImportPath}} pkg/parser
+      {{.ImportPath}} pkg/parser
+      SUCCESS
+      """
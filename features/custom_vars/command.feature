@@ -0,0 +1,93 @@
+Feature: define a custom variable with the output of a command
+
+  Scenario: filtering the output of a command
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.go",
+            "vars": [
+              {
+                "name": "package",
+                "source": "command",
+                "command": "echo import path example.com/app/{{file_dir}}",
+                "filter": "^import path (\\S+)"
+              }
+            ],
+            "run": "echo go test {{package}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "parser/lexer_test.go" }'
+    Then it prints
+      """
+      executing: echo go test example.com/app/parser
+      go test example.com/app/parser
+      SUCCESS
+      """
+
+  Scenario: the command fails
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "vars": [
+              {
+                "name": "branch",
+                "source": "command",
+                "command": "false"
+              }
+            ],
+            "run": "echo testing branch {{branch}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-all" }'
+    Then it prints
+      """
+      Error: the command "false" of a variable failed
+      """
+    # the server keeps running
+    When receiving the command '{ "command": "custom-command", "run": "echo still running" }'
+    Then it prints
+      """
+      executing: echo still running
+      still running
+      SUCCESS
+      """
+
+  Scenario: explaining a variable with a command
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "vars": [
+              {
+                "name": "run_count",
+                "source": "command",
+                "command": "sh -c 'echo run >> runs.txt && wc -l < runs.txt'",
+                "filter": "(\\d+)"
+              }
+            ],
+            "run": "echo run {{run_count}}"
+          }
+        ]
+      }
+      """
+    When I run 'contest explain "{ \"command\": \"test-all\" }"'
+    Then it exits with output containing
+      """
+      actions[0] test-all: match
+        run_count = <command: sh -c 'echo run >> runs.txt && wc -l < runs.txt'>
+      command: echo run <command: sh -c 'echo run >> runs.txt && wc -l < runs.txt'>
+      """
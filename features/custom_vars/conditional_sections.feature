@@ -0,0 +1,41 @@
+Feature: conditional sections in the run command
+
+  Background:
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "vars": [
+              {
+                "name": "feature",
+                "source": "file",
+                "filter": "^plugins/(\\w+)/"
+              }
+            ],
+            "run": "echo cargo test{{#if feature}} --features {{feature}}{{/if}}{{#unless feature}} --lib{{/unless}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+
+  Scenario: the variable has a value
+    When receiving the command '{ "command": "test-file", "file": "plugins/db/query.rs" }'
+    Then it prints
+      """
+      executing: echo cargo test --features db
+      cargo test --features db
+      SUCCESS
+      """
+
+  Scenario: the variable is empty
+    When receiving the command '{ "command": "test-file", "file": "src/lib.rs" }'
+    Then it prints
+      """
+      executing: echo cargo test --lib
+      cargo test --lib
+      SUCCESS
+      """
@@ -0,0 +1,53 @@
+Feature: define a custom variable from the scopes that enclose the cursor
+
+  Background:
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file-line",
+            "files": "**/*.py",
+            "vars": [
+              {
+                "name": "test_path",
+                "source": "enclosingScopes",
+                "filter": "^\\s*(?:class|def) (\\w+)",
+                "separator": "::"
+              }
+            ],
+            "run": "echo pytest {{file}}::{{test_path}}"
+          }
+        ]
+      }
+      """
+    And file "test_parser.py" with content
+      """
+      class TestParser:
+          def test_empty(self):
+              assert parse("") == []
+
+          fixtures = []
+
+          def test_valid(self):
+              assert parse("1") == [1]
+      """
+    And Contest is running
+
+  Scenario: cursor inside a nested test
+    When receiving the command '{ "command": "test-file-line", "file": "test_parser.py", "line": 9 }'
+    Then it prints
+      """
+      executing: echo pytest test_parser.py::TestParser::test_valid
+      pytest test_parser.py::TestParser::test_valid
+      SUCCESS
+      """
+
+  Scenario: cursor outside of a test
+    When receiving the command '{ "command": "test-file-line", "file": "test_parser.py", "line": 6 }'
+    Then it prints
+      """
+      executing: echo pytest test_parser.py::TestParser
+      pytest test_parser.py::TestParser
+      SUCCESS
+      """
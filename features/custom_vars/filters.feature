@@ -0,0 +1,43 @@
+Feature: transforming placeholder values with filters
+
+  Scenario: chaining filters
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.py",
+            "run": "echo {{ file_stem | replace:\"test_\":\"\" | snake_case }} {{ file_module_colons | shell_quote }} {{ unknown | default:\"all\" }}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "tests/test_MyParser.py" }'
+    Then it prints
+      """
+      executing: echo my_parser tests::test_MyParser all
+      my_parser tests::test_MyParser all
+      SUCCESS
+      """
+
+  Scenario: invalid filter
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-all",
+            "run": "make {{ target | zonk }}"
+          }
+        ]
+      }
+      """
+    When I run "contest validate"
+    Then it fails with this output
+      """
+      error in actions[0]: invalid placeholder in "make {{ target | zonk }}" at column 18: unknown filter: zonk
+
+      Error: the configuration file contains 1 error
+      """
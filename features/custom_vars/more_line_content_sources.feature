@@ -0,0 +1,100 @@
+Feature: define custom variables by scanning the file in other directions
+
+  Background:
+    Given file "parser_test.go" with content
+      """
+      package parser
+
+      func TestOne(t *testing.T) {
+      }
+
+      func TestTwo(t *testing.T) {
+      }
+      """
+
+  Scenario: current or below
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file-line",
+            "files": "**/*.go",
+            "vars": [
+              {
+                "name": "test_name",
+                "source": "currentOrBelowLineContent",
+                "filter": "^func (\\w+)\\("
+              }
+            ],
+            "run": "echo go test -run {{test_name}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file-line", "file": "parser_test.go", "line": 6 }'
+    Then it prints
+      """
+      executing: echo go test -run TestTwo
+      go test -run TestTwo
+      SUCCESS
+      """
+
+  Scenario: nearest
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file-line",
+            "files": "**/*.go",
+            "vars": [
+              {
+                "name": "test_name",
+                "source": "nearestLineContent",
+                "filter": "^func (\\w+)\\("
+              }
+            ],
+            "run": "echo go test -run {{test_name}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file-line", "file": "parser_test.go", "line": 6 }'
+    Then it prints
+      """
+      executing: echo go test -run TestTwo
+      go test -run TestTwo
+      SUCCESS
+      """
+
+  Scenario: first match in file
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.go",
+            "vars": [
+              {
+                "name": "package",
+                "source": "firstMatchInFile",
+                "filter": "^package (\\w+)"
+              }
+            ],
+            "run": "echo go test ./{{package}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "parser_test.go" }'
+    Then it prints
+      """
+      executing: echo go test ./parser
+      go test ./parser
+      SUCCESS
+      """
@@ -0,0 +1,30 @@
+Feature: named capture groups in variable filters
+
+  Scenario: using the named groups as placeholders
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.py",
+            "vars": [
+              {
+                "name": "test",
+                "source": "file",
+                "filter": "^(?<package>\\w+)/test_(?<module>\\w+)\\.py$"
+              }
+            ],
+            "run": "echo testing module {{test.module}} in package {{test.package}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+    When receiving the command '{ "command": "test-file", "file": "parser/test_lexer.py" }'
+    Then it prints
+      """
+      executing: echo testing module lexer in package parser
+      testing module lexer in package parser
+      SUCCESS
+      """
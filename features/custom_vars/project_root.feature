@@ -0,0 +1,58 @@
+Feature: define a custom variable with the root directory of the project that contains the file
+
+  Background:
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file",
+            "files": "**/*.rs",
+            "vars": [
+              {
+                "name": "package_dir",
+                "source": "projectRoot",
+                "markers": ["Cargo.toml"]
+              }
+            ],
+            "run": "sh -c 'echo testing {{package_dir}} in $(basename $(pwd))'",
+            "cwd": "{{package_dir}}"
+          }
+        ]
+      }
+      """
+    And file "crates/parser/Cargo.toml" with content
+      """
+      [package]
+      name = "parser"
+      """
+    And file "crates/parser/src/lexer/mod.rs" with content
+      """
+      mod tokens;
+      """
+    And Contest is running
+
+  Scenario: file in a project
+    When receiving the command '{ "command": "test-file", "file": "crates/parser/src/lexer/mod.rs" }'
+    Then it prints
+      """
+      executing: sh -c 'echo testing crates/parser in $(basename $(pwd))'
+      testing crates/parser in parser
+      SUCCESS
+      """
+
+  Scenario: file outside of a project
+    When receiving the command '{ "command": "test-file", "file": "scripts/build.rs" }'
+    Then it prints
+      """
+      Error: cannot find the project root of scripts/build.rs
+      None of the directories containing this file contain one of these files: Cargo.toml
+      """
+    # the server keeps running
+    When receiving the command '{ "command": "test-file", "file": "crates/parser/src/lexer/mod.rs" }'
+    Then it prints
+      """
+      executing: sh -c 'echo testing crates/parser in $(basename $(pwd))'
+      testing crates/parser in parser
+      SUCCESS
+      """
@@ -20,5 +20,5 @@ Feature: client sends unknown fields in the command
       """
       Error: cannot parse command received from client: { "command": "test-all", "foo": 1, "bar": 2 }
 
-      unknown field `foo`, expected one of `command`, `file`, `line`, `run`
+      unknown field `foo`, expected one of `command`, `file`, `line`, `end_line`, `run`
       """
@@ -0,0 +1,40 @@
+Feature: run all tests in a directory
+
+  Background:
+    Given file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-dir",
+            "dirs": "pkg/**",
+            "run": "echo testing package {{dir}}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+
+  Scenario: receiving a file in a matching directory
+    When receiving the command '{ "command": "test-dir", "file": "pkg/parser/lexer.go" }'
+    Then it prints
+      """
+      executing: echo testing package pkg/parser
+      testing package pkg/parser
+      """
+
+  Scenario: receiving a directory
+    When receiving the command '{ "command": "test-dir", "file": "pkg/parser/" }'
+    Then it prints
+      """
+      executing: echo testing package pkg/parser
+      testing package pkg/parser
+      """
+
+  Scenario: receiving a directory that doesn't match an existing rule
+    When receiving the command '{ "command": "test-dir", "file": "cmd/server/main.go" }'
+    Then it prints
+      """
+      Error: cannot determine command for trigger: test-dir cmd/server
+      Please make sure that this action is listed in contest.json
+      """
@@ -0,0 +1,72 @@
+Feature: run all tests in a selected range of lines
+
+  Background:
+    Given file "parser.rs" with content
+      """
+      #[test]
+      fn empty() {
+        assert!(parse("").is_empty());
+      }
+
+      #[test]
+      fn single_word() {
+        assert_eq!(parse("one").len(), 1);
+      }
+
+      #[test]
+      fn two_words() {
+        assert_eq!(parse("one two").len(), 2);
+      }
+      """
+    And file "contest.json" with content
+      """
+      {
+        "actions": [
+          {
+            "type": "test-file-range",
+            "files": "**/*.rs",
+            "vars": [
+              {
+                "name": "tests",
+                "source": "rangeContent",
+                "filter": "\\bfn (\\w+)\\("
+              }
+            ],
+            "run": "echo testing {{ tests | join:\" or \" }} in lines {{ line }}-{{ end_line }}"
+          }
+        ]
+      }
+      """
+    And Contest is running
+
+  Scenario: selecting from inside a test to the next test
+    When receiving the command '{ "command": "test-file-range", "file": "parser.rs", "line": 4, "end_line": 9 }'
+    Then it prints
+      """
+      executing: echo testing empty or single_word in lines 4-9
+      testing empty or single_word in lines 4-9
+      """
+
+  Scenario: selecting from between two tests
+    When receiving the command '{ "command": "test-file-range", "file": "parser.rs", "line": 6, "end_line": 9 }'
+    Then it prints
+      """
+      executing: echo testing single_word in lines 6-9
+      testing single_word in lines 6-9
+      """
+
+  Scenario: selecting all tests
+    When receiving the command '{ "command": "test-file-range", "file": "parser.rs", "line": 18, "end_line": 1 }'
+    Then it prints
+      """
+      executing: echo testing empty or single_word or two_words in lines 1-18
+      testing empty or single_word or two_words in lines 1-18
+      """
+
+  Scenario: missing end line
+    When receiving the command '{ "command": "test-file-range", "file": "parser.rs", "line": 4 }'
+    Then it prints
+      """
+      Error: invalid trigger received: { "command": "test-file-range", "file": "parser.rs", "line": 4 }
+      missing "end_line" field
+      """
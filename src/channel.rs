@@ -1,17 +1,67 @@
 //! cross-thread communication via a message channel
 
+use crate::client::Responder;
+use std::collections::VecDeque;
 use std::sync::mpsc;
+use std::time::Duration;
 
 pub type Sender = mpsc::Sender<Signal>;
 
 /// Signals that can be sent over the channel.
 pub enum Signal {
-  /// A command was received from the FIFO
-  ReceivedLine(String),
+  /// A command was received from a client
+  ReceivedLine {
+    line: String,
+    /// sends the progress of executing this command back to the client
+    responder: Responder,
+  },
+  /// A file in the workspace has changed
+  FileChanged(String),
+  /// The configuration file has changed
+  ConfigChanged,
   /// Received Ctrl-C
   Exit,
 }
 
-pub fn create() -> (mpsc::Sender<Signal>, mpsc::Receiver<Signal>) {
-  mpsc::channel::<Signal>()
+/// The receiving end of the channel.
+/// Signals that arrive while a test is running can be put back to process them later.
+pub struct Receiver {
+  receiver: mpsc::Receiver<Signal>,
+  backlog: VecDeque<Signal>,
+}
+
+impl Receiver {
+  /// provides the next signal to process, blocks until one is available
+  pub fn recv(&mut self) -> Option<Signal> {
+    match self.backlog.pop_front() {
+      Some(signal) => Some(signal),
+      None => self.receiver.recv().ok(),
+    }
+  }
+
+  /// provides a newly arrived signal, waits at most the given duration for one to arrive
+  pub fn recv_timeout(&self, timeout: Duration) -> Option<Signal> {
+    self.receiver.recv_timeout(timeout).ok()
+  }
+
+  /// makes the given signal the next one to process
+  pub fn put_back(&mut self, signal: Signal) {
+    self.backlog.push_front(signal);
+  }
+
+  /// processes the given signal after all other signals received so far
+  pub fn postpone(&mut self, signal: Signal) {
+    self.backlog.push_back(signal);
+  }
+}
+
+pub fn create() -> (Sender, Receiver) {
+  let (sender, receiver) = mpsc::channel::<Signal>();
+  (
+    sender,
+    Receiver {
+      receiver,
+      backlog: VecDeque::new(),
+    },
+  )
 }
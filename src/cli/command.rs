@@ -16,10 +16,24 @@ pub enum Command {
     /// the client-side trigger to execute
     trigger: String,
   },
+  /// List the past test runs, or show the output of the given one
+  History {
+    /// the id of the test run to show
+    id: Option<usize>,
+  },
   /// Create an example configuration file
   Init,
+  /// Show how the given client-side trigger resolves into a command without running it
+  Explain {
+    /// the client-side trigger to explain
+    trigger: String,
+  },
   /// Execute the received triggers from the pipe
   Start,
+  /// Report all problems in the configuration file
+  Validate,
+  /// Execute the received triggers and test files when they change
+  Watch,
 }
 
 impl Command {
@@ -1,8 +1,9 @@
-use crate::fifo;
+use crate::client::{fifo, socket};
 use std::fs;
 
 pub fn exit(err: &str) -> ! {
   println!("ERROR: {err}");
   let _ = fs::remove_file(fifo::FILE_NAME);
+  let _ = fs::remove_file(socket::FILE_NAME);
   std::process::exit(1);
 }
@@ -13,5 +13,7 @@ pub fn error_color(success: &subshell::Outcome) -> termcolor::Color {
   match success {
     subshell::Outcome::TestPass => termcolor::Color::Green,
     subshell::Outcome::TestFail => termcolor::Color::Red,
+    subshell::Outcome::TestTimeout => termcolor::Color::Magenta,
+    subshell::Outcome::Superseded | subshell::Outcome::Aborted => termcolor::Color::Yellow,
   }
 }
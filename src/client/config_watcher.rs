@@ -0,0 +1,43 @@
+//! watches the configuration file for changes
+
+use super::debounced_watch;
+use crate::Result;
+use crate::channel::{self, Signal};
+use notify::RecursiveMode;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// how long to wait for further changes to the configuration file before reloading it
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Reports changes to the configuration file.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+  pub path: PathBuf,
+}
+
+impl ConfigWatcher {
+  /// constructs a watcher for the configuration file at the given path
+  #[must_use]
+  pub fn for_file(path: &Path) -> Self {
+    ConfigWatcher { path: path.to_path_buf() }
+  }
+
+  pub fn listen(&self, sender: channel::Sender) -> Result<()> {
+    // editors often save files by replacing them, so watch the directory containing the file instead of the file itself
+    let Some(dirpath) = self.path.parent() else {
+      return Ok(());
+    };
+    let Some(filename) = self.path.file_name().map(ToOwned::to_owned) else {
+      return Ok(());
+    };
+    debounced_watch::watch(
+      dirpath,
+      RecursiveMode::NonRecursive,
+      DEBOUNCE,
+      "configuration watcher",
+      move |path| (path.file_name() == Some(&filename)).then_some(()),
+      move |_| sender.send(Signal::ConfigChanged).unwrap_or_else(|err| println!("communication channel failure: {err}")),
+    )
+  }
+}
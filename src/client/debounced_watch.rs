@@ -0,0 +1,52 @@
+//! watches a directory and reports changes once they settle down
+
+use crate::{Result, UserError};
+use notify::{EventKind, RecursiveMode, Watcher as _};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use std::{mem, thread};
+
+/// Watches the given directory in the background.
+/// Calls `relevant` for each created or modified path and collects the values it returns.
+/// Once no further relevant changes arrive within the debounce period, calls `settled` with the collected values.
+/// The `name` of the watcher shows up in error messages.
+pub fn watch<T, R, S>(dirpath: &Path, mode: RecursiveMode, debounce: Duration, name: &'static str, relevant: R, mut settled: S) -> Result<()>
+where
+  T: PartialEq + Send + 'static,
+  R: Fn(&Path) -> Option<T> + Send + 'static,
+  S: FnMut(Vec<T>) + Send + 'static,
+{
+  let (event_sender, event_receiver) = mpsc::channel();
+  let mut watcher = notify::recommended_watcher(event_sender).map_err(|err| UserError::WatcherCannotStart { err: err.to_string() })?;
+  watcher.watch(dirpath, mode).map_err(|err| UserError::WatcherCannotStart { err: err.to_string() })?;
+  thread::spawn(move || {
+    // the watcher stops watching when it gets dropped
+    let _watcher = watcher;
+    let mut changes: Vec<T> = vec![];
+    loop {
+      // wait for more changes as long as they keep coming in within the debounce period
+      let event = if changes.is_empty() {
+        event_receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+      } else {
+        event_receiver.recv_timeout(debounce)
+      };
+      match event {
+        Ok(Ok(event)) => {
+          if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            continue;
+          }
+          for change in event.paths.iter().filter_map(|path| relevant(path)) {
+            if !changes.contains(&change) {
+              changes.push(change);
+            }
+          }
+        }
+        Ok(Err(err)) => println!("{name} failure: {err}"),
+        Err(RecvTimeoutError::Timeout) => settled(mem::take(&mut changes)),
+        Err(RecvTimeoutError::Disconnected) => return,
+      }
+    }
+  });
+  Ok(())
+}
@@ -1,5 +1,6 @@
 //! manages and reads the FIFO pipe
 
+use super::Responder;
 use crate::channel::Signal;
 use crate::{Result, UserError, channel, cli};
 use std::fs::{self, File};
@@ -47,8 +48,11 @@ impl Fifo {
         let reader = BufReader::new(&file);
         for line in reader.lines() {
           match line {
-            Ok(text) => sender
-              .send(Signal::ReceivedLine(text))
+            Ok(line) => sender
+              .send(Signal::ReceivedLine {
+                line,
+                responder: Responder::default(),
+              })
               .unwrap_or_else(|err| println!("communication channel failure: {err}")),
             Err(err) => cli::exit(&err.to_string()),
           }
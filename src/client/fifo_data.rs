@@ -15,6 +15,9 @@ pub struct FifoTriggerData {
   pub command: String,
   pub file: Option<String>,
   pub line: Option<usize>,
+  /// the last line of a selected range
+  #[serde(rename = "end_line")]
+  pub end_line: Option<usize>,
   pub run: Option<String>,
 }
 
@@ -127,6 +130,29 @@ mod tests {
       }
     }
 
+    mod test_file_range {
+      use crate::client::FifoTrigger;
+      use crate::client::fifo_data::FifoTriggerData;
+      use big_s::S;
+
+      #[test]
+      fn end_line() {
+        let give = S(r#"{ "command": "test-file-range", "file": "foo.rs", "line": 12, "end_line": 30 }"#);
+        let have = FifoTrigger::parse(give.clone()).unwrap();
+        let want = FifoTrigger {
+          data: FifoTriggerData {
+            command: S("test-file-range"),
+            file: Some(S("foo.rs")),
+            line: Some(12),
+            end_line: Some(30),
+            ..FifoTriggerData::default()
+          },
+          original_line: give,
+        };
+        assert_eq!(have, want);
+      }
+    }
+
     mod test_function {
       use crate::client::FifoTrigger;
       use crate::client::fifo_data::FifoTriggerData;
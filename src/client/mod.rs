@@ -1,9 +1,18 @@
 //! everything around communicating with the client
 
+mod config_watcher;
+mod debounced_watch;
 pub mod fifo;
 mod fifo_data;
+mod reply;
+pub mod socket;
 mod trigger;
+mod watcher;
 
+pub use config_watcher::ConfigWatcher;
 pub use fifo::Fifo;
 pub use fifo_data::FifoTrigger;
+pub use reply::{Reply, Responder};
+pub use socket::Socket;
 pub use trigger::Trigger;
+pub use watcher::Watcher;
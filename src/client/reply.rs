@@ -0,0 +1,107 @@
+//! replies that Contest sends back to clients connected via the socket
+
+use crate::UserError;
+use serde::Serialize;
+use std::sync::mpsc;
+
+/// progress report about a trigger received from a client
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum Reply {
+  /// Contest accepted the trigger and runs the given command now
+  Accepted { command: String },
+  /// Contest cannot execute the trigger
+  Rejected {
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    guidance: Option<String>,
+  },
+  /// the command for the trigger has finished
+  Finished { outcome: String },
+}
+
+impl Reply {
+  /// indicates whether this is the last reply for a trigger
+  #[must_use]
+  pub fn is_final(&self) -> bool {
+    match self {
+      Reply::Accepted { command: _ } => false,
+      Reply::Rejected { error: _, guidance: _ } | Reply::Finished { outcome: _ } => true,
+    }
+  }
+}
+
+impl From<UserError> for Reply {
+  fn from(err: UserError) -> Self {
+    let (error, guidance) = err.messages();
+    Reply::Rejected { error, guidance }
+  }
+}
+
+/// sends replies to the client that sent a trigger
+///
+/// Clients that cannot receive replies, like the FIFO, use the default `Responder` that discards all replies.
+#[derive(Default)]
+pub struct Responder {
+  sender: Option<mpsc::Sender<Reply>>,
+}
+
+impl Responder {
+  #[must_use]
+  pub fn new(sender: mpsc::Sender<Reply>) -> Self {
+    Responder { sender: Some(sender) }
+  }
+
+  pub fn send(&self, reply: Reply) {
+    if let Some(sender) = &self.sender {
+      // the client might have disconnected already, nothing to do in this case
+      let _ = sender.send(reply);
+    }
+  }
+
+  /// indicates whether a client receives the replies
+  #[must_use]
+  pub fn has_client(&self) -> bool {
+    self.sender.is_some()
+  }
+
+  /// lets the client know that its trigger cannot be executed because of the given error
+  #[must_use]
+  pub fn reject(&self, err: UserError) -> UserError {
+    self.send(Reply::from(err.clone()));
+    err
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod serialize {
+    use crate::client::Reply;
+    use big_s::S;
+
+    #[test]
+    fn accepted() {
+      let reply = Reply::Accepted { command: S("make test") };
+      let have = serde_json::to_string(&reply).unwrap();
+      assert_eq!(have, r#"{"status":"accepted","command":"make test"}"#);
+    }
+
+    #[test]
+    fn rejected_without_guidance() {
+      let reply = Reply::Rejected {
+        error: S("broken"),
+        guidance: None,
+      };
+      let have = serde_json::to_string(&reply).unwrap();
+      assert_eq!(have, r#"{"status":"rejected","error":"broken"}"#);
+    }
+
+    #[test]
+    fn finished() {
+      let reply = Reply::Finished { outcome: S("pass") };
+      let have = serde_json::to_string(&reply).unwrap();
+      assert_eq!(have, r#"{"status":"finished","outcome":"pass"}"#);
+    }
+  }
+}
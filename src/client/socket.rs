@@ -0,0 +1,138 @@
+//! manages the Unix domain socket that clients can connect to
+
+use super::{Reply, Responder};
+use crate::channel::Signal;
+use crate::{Result, UserError, channel};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::{fs, thread};
+
+pub const FILE_NAME: &str = ".contest.sock";
+
+/// A Unix domain socket
+#[derive(Debug)]
+pub struct Socket {
+  pub filepath: PathBuf,
+}
+
+impl Socket {
+  /// constructs a socket in the given directory
+  #[must_use]
+  pub fn in_dir(dirpath: &Path) -> Self {
+    Socket {
+      filepath: dirpath.join(FILE_NAME),
+    }
+  }
+
+  // creates the socket on the filesystem
+  fn create(&self) -> Result<UnixListener> {
+    if self.filepath.exists() {
+      return Err(UserError::SocketAlreadyExists { path: self.path_str() });
+    }
+    UnixListener::bind(&self.filepath).map_err(|err| UserError::SocketCannotCreate {
+      path: self.path_str(),
+      err: err.to_string(),
+    })
+  }
+
+  pub fn listen(&self, sender: channel::Sender) -> Result<()> {
+    let listener = self.create()?;
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        match stream {
+          Ok(stream) => {
+            let sender = sender.clone();
+            thread::spawn(move || {
+              if let Err(err) = serve(stream, &sender) {
+                println!("socket connection failure: {err}");
+              }
+            });
+          }
+          Err(err) => println!("cannot accept socket connection: {err}"),
+        }
+      }
+    });
+    Ok(())
+  }
+
+  pub fn delete(&self) -> Result<()> {
+    fs::remove_file(&self.filepath).map_err(|e| UserError::SocketCannotDelete {
+      err: e.to_string(),
+      path: self.path_str(),
+    })
+  }
+
+  /// provides the path of this socket as a string
+  #[must_use]
+  pub fn path_str(&self) -> String {
+    self.filepath.display().to_string()
+  }
+}
+
+/// forwards the triggers received over the given connection to the given channel
+/// and sends the replies for each trigger back to the client
+fn serve(stream: UnixStream, sender: &channel::Sender) -> io::Result<()> {
+  let mut writer = stream.try_clone()?;
+  let reader = BufReader::new(stream);
+  for line in reader.lines() {
+    let (reply_sender, reply_receiver) = mpsc::channel::<Reply>();
+    let signal = Signal::ReceivedLine {
+      line: line?,
+      responder: Responder::new(reply_sender),
+    };
+    if let Err(err) = sender.send(signal) {
+      println!("communication channel failure: {err}");
+      return Ok(());
+    }
+    for reply in reply_receiver {
+      let is_final = reply.is_final();
+      let text = serde_json::to_string(&reply).map_err(io::Error::other)?;
+      writeln!(writer, "{text}")?;
+      if is_final {
+        break;
+      }
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::UserError;
+  use crate::client::Socket;
+  use big_s::S;
+  use std::fs;
+
+  #[test]
+  fn socket_create_does_not_exist() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let socket = Socket::in_dir(temp_dir.path());
+    let _listener = socket.create().unwrap();
+    let files: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(files, vec![socket.filepath]);
+  }
+
+  #[test]
+  fn socket_create_exists() -> Result<(), String> {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let socket = Socket::in_dir(temp_dir.path());
+    let _listener = socket.create().unwrap();
+    match socket.create() {
+      Err(UserError::SocketAlreadyExists { path: _ }) => Ok(()),
+      Err(err) => Err(err.messages().0),
+      Ok(_) => Err(S("should not create second socket")),
+    }
+  }
+
+  #[test]
+  fn socket_delete() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let socket = Socket::in_dir(temp_dir.path());
+    let _listener = socket.create().unwrap();
+    socket.delete().unwrap();
+    let file_count = fs::read_dir(temp_dir.path()).unwrap().count();
+    assert_eq!(0, file_count);
+  }
+}
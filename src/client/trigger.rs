@@ -3,13 +3,31 @@
 use super::fifo_data::FifoTrigger;
 use crate::UserError;
 use std::fmt::Display;
+use std::path::Path;
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum Trigger {
   TestAll,
-  TestFile { file: String },
-  TestFileLine { file: String, line: usize },
-  CustomCommand { run: String },
+  TestFile {
+    file: String,
+  },
+  TestFileLine {
+    file: String,
+    line: usize,
+  },
+  /// tests all tests in the given range of 1-based lines
+  TestFileRange {
+    file: String,
+    line: usize,
+    end_line: usize,
+  },
+  /// tests the directory, for example the package, that contains a file
+  TestDir {
+    dir: String,
+  },
+  CustomCommand {
+    run: String,
+  },
   RepeatLastTest,
   Quit,
 }
@@ -20,6 +38,8 @@ impl Display for Trigger {
       Trigger::TestAll => f.write_str("test-all"),
       Trigger::TestFile { file } => write!(f, "test-file {file}"),
       Trigger::TestFileLine { file, line } => write!(f, "test-file-line {file}:{line}"),
+      Trigger::TestFileRange { file, line, end_line } => write!(f, "test-file-range {file}:{line}-{end_line}"),
+      Trigger::TestDir { dir } => write!(f, "test-dir {dir}"),
       Trigger::CustomCommand { run } => write!(f, "custom-command {run}"),
       Trigger::RepeatLastTest => f.write_str("repeat-test"),
       Trigger::Quit => f.write_str("quit"),
@@ -48,12 +68,38 @@ impl TryFrom<FifoTrigger> for Trigger {
         (Some(_), None) => Err(UserError::MissingLineInTrigger { original: fifo.original_line }),
         (None, None) => Err(UserError::MissingFileAndLineInTrigger { original: fifo.original_line }),
       },
+      "test-file-range" => match (fifo.data.file, fifo.data.line, fifo.data.end_line) {
+        // editors send the range in the direction of the selection
+        (Some(file), Some(line), Some(end_line)) => Ok(Trigger::TestFileRange {
+          file,
+          line: line.min(end_line),
+          end_line: line.max(end_line),
+        }),
+        (None, _, _) => Err(UserError::MissingFileInTrigger { original: fifo.original_line }),
+        (Some(_), None, _) => Err(UserError::MissingLineInTrigger { original: fifo.original_line }),
+        (Some(_), Some(_), None) => Err(UserError::MissingEndLineInTrigger { original: fifo.original_line }),
+      },
+      "test-dir" => match fifo.data.file {
+        Some(file) => Ok(Trigger::TestDir { dir: containing_dir(&file) }),
+        None => Err(UserError::MissingFileInTrigger { original: fifo.original_line }),
+      },
       "quit" => Ok(Trigger::Quit),
       _ => Err(UserError::UnknownTrigger { source: fifo.data.command }),
     }
   }
 }
 
+/// provides the given path if it denotes a directory by ending in a slash, or else the directory that contains the given file
+fn containing_dir(file: &str) -> String {
+  if file.ends_with('/') {
+    return file.trim_end_matches('/').to_owned();
+  }
+  match Path::new(file).parent() {
+    Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().to_string(),
+    _ => String::from("."),
+  }
+}
+
 impl TryFrom<String> for Trigger {
   type Error = UserError;
 
@@ -168,6 +214,106 @@ mod tests {
       }
     }
 
+    mod test_file_range {
+      use crate::UserError;
+      use crate::client::fifo_data::FifoTriggerData;
+      use crate::client::{FifoTrigger, Trigger};
+      use big_s::S;
+
+      fn trigger(line: Option<usize>, end_line: Option<usize>) -> FifoTrigger {
+        FifoTrigger {
+          data: FifoTriggerData {
+            command: S("test-file-range"),
+            file: Some(S("foo.rs")),
+            line,
+            end_line,
+            ..FifoTriggerData::default()
+          },
+          ..FifoTrigger::default()
+        }
+      }
+
+      #[test]
+      fn valid() {
+        let have = Trigger::try_from(trigger(Some(12), Some(30))).unwrap();
+        let want = Trigger::TestFileRange {
+          file: S("foo.rs"),
+          line: 12,
+          end_line: 30,
+        };
+        assert_eq!(have, want);
+      }
+
+      #[test]
+      fn selected_upwards() {
+        let have = Trigger::try_from(trigger(Some(30), Some(12))).unwrap();
+        let want = Trigger::TestFileRange {
+          file: S("foo.rs"),
+          line: 12,
+          end_line: 30,
+        };
+        assert_eq!(have, want);
+      }
+
+      #[test]
+      fn missing_end_line() {
+        let have = Trigger::try_from(trigger(Some(12), None));
+        let want = Err(UserError::MissingEndLineInTrigger { original: String::new() });
+        assert_eq!(have, want);
+      }
+    }
+
+    mod test_dir {
+      use crate::client::fifo_data::FifoTriggerData;
+      use crate::client::{FifoTrigger, Trigger};
+      use big_s::S;
+
+      fn trigger(file: Option<&str>) -> FifoTrigger {
+        FifoTrigger {
+          data: FifoTriggerData {
+            command: S("test-dir"),
+            file: file.map(str::to_owned),
+            ..FifoTriggerData::default()
+          },
+          ..FifoTrigger::default()
+        }
+      }
+
+      #[test]
+      fn file() {
+        let have = Trigger::try_from(trigger(Some("pkg/parser/lexer.go"))).unwrap();
+        let want = Trigger::TestDir { dir: S("pkg/parser") };
+        assert_eq!(have, want);
+      }
+
+      #[test]
+      fn file_in_current_dir() {
+        let have = Trigger::try_from(trigger(Some("lexer.go"))).unwrap();
+        let want = Trigger::TestDir { dir: S(".") };
+        assert_eq!(have, want);
+      }
+
+      #[test]
+      fn dir() {
+        let have = Trigger::try_from(trigger(Some("pkg/parser/"))).unwrap();
+        let want = Trigger::TestDir { dir: S("pkg/parser") };
+        assert_eq!(have, want);
+      }
+
+      #[test]
+      fn dir_without_trailing_slash() {
+        let have = Trigger::try_from(trigger(Some("pkg/parser"))).unwrap();
+        let want = Trigger::TestDir { dir: S("pkg") };
+        assert_eq!(have, want);
+      }
+
+      #[test]
+      fn missing_file() {
+        let have = Trigger::try_from(trigger(None));
+        assert!(have.is_err());
+      }
+    }
+
     mod test_function {
       use crate::client::fifo_data::FifoTriggerData;
       use crate::client::{FifoTrigger, Trigger};
@@ -0,0 +1,224 @@
+//! watches the workspace for file changes
+
+use super::{debounced_watch, fifo, socket};
+use crate::channel::Signal;
+use crate::config::Watch;
+use crate::{Result, UserError, channel, history};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use notify::RecursiveMode;
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+/// Watches the files in a directory and reports changed files.
+#[derive(Debug)]
+pub struct Watcher {
+  pub dirpath: PathBuf,
+}
+
+impl Watcher {
+  /// constructs a watcher for the given directory
+  #[must_use]
+  pub fn in_dir(dirpath: &Path) -> Self {
+    Watcher { dirpath: dirpath.to_path_buf() }
+  }
+
+  pub fn listen(&self, config: &Watch, sender: channel::Sender) -> Result<()> {
+    let filter = Filter {
+      ignore: config.ignore.clone(),
+      gitignores: if config.gitignore { load_gitignores(&self.dirpath)? } else { vec![] },
+    };
+    // the paths of file events are canonical
+    let dirpath = self.dirpath.canonicalize().unwrap_or_else(|_| self.dirpath.clone());
+    debounced_watch::watch(
+      &self.dirpath,
+      RecursiveMode::Recursive,
+      config.debounce,
+      "file watcher",
+      move |path| filter.relevant_file(&dirpath, path),
+      move |files| {
+        for file in files {
+          sender
+            .send(Signal::FileChanged(file))
+            .unwrap_or_else(|err| println!("communication channel failure: {err}"));
+        }
+      },
+    )
+  }
+}
+
+/// determines which changed files should trigger tests
+struct Filter {
+  ignore: Vec<glob::Pattern>,
+  /// the .gitignore files in the workspace and the directories they apply to, the deepest directories first
+  gitignores: Vec<(PathBuf, Gitignore)>,
+}
+
+impl Filter {
+  /// provides the path of the given changed file relative to the given directory,
+  /// or `None` if changes to this file should not trigger tests
+  fn relevant_file(&self, dirpath: &Path, path: &Path) -> Option<String> {
+    if !path.is_file() {
+      return None;
+    }
+    let relative = path.strip_prefix(dirpath).ok()?;
+    if relative.starts_with(".git") || relative == Path::new(fifo::FILE_NAME) || relative == Path::new(socket::FILE_NAME) || relative == Path::new(history::FILE_NAME) {
+      return None;
+    }
+    if self.gitignored(relative) {
+      return None;
+    }
+    let file = relative.to_string_lossy().to_string();
+    if self.ignore.iter().any(|pattern| pattern.matches(&file)) {
+      return None;
+    }
+    Some(file)
+  }
+
+  /// indicates whether the closest .gitignore file that has an opinion about the given relative path ignores it
+  fn gitignored(&self, relative: &Path) -> bool {
+    for (dir, gitignore) in &self.gitignores {
+      let Ok(below) = relative.strip_prefix(dir) else {
+        continue;
+      };
+      match gitignore.matched_path_or_any_parents(below, false) {
+        Match::None => {}
+        matched => return matched.is_ignore(),
+      }
+    }
+    false
+  }
+}
+
+/// loads the .gitignore files in the given directory and its subdirectories
+fn load_gitignores(dirpath: &Path) -> Result<Vec<(PathBuf, Gitignore)>> {
+  let mut result = vec![];
+  let walk = WalkBuilder::new(dirpath).hidden(false).filter_entry(|entry| entry.file_name() != ".git").build();
+  for entry in walk.flatten() {
+    if entry.file_name() != ".gitignore" {
+      continue;
+    }
+    let path = entry.path();
+    let Some(dir) = path.parent() else {
+      continue;
+    };
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(err) = builder.add(path) {
+      return Err(UserError::CannotReadFile {
+        path: path.to_string_lossy().to_string(),
+        err: err.to_string(),
+      });
+    }
+    let gitignore = builder.build().map_err(|err| UserError::CannotReadFile {
+      path: path.to_string_lossy().to_string(),
+      err: err.to_string(),
+    })?;
+    result.push((dir.strip_prefix(dirpath).unwrap_or(dir).to_path_buf(), gitignore));
+  }
+  result.sort_by_key(|(dir, _)| Reverse(dir.components().count()));
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod relevant_file {
+    use super::super::{Filter, load_gitignores};
+    use big_s::S;
+    use std::fs;
+
+    #[test]
+    fn normal_file() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      let path = temp_dir.path().join("foo.rs");
+      fs::write(&path, "").unwrap();
+      let filter = Filter {
+        ignore: vec![],
+        gitignores: vec![],
+      };
+      let have = filter.relevant_file(temp_dir.path(), &path);
+      assert_eq!(have, Some(S("foo.rs")));
+    }
+
+    #[test]
+    fn ignored_by_glob() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      fs::create_dir(temp_dir.path().join("dist")).unwrap();
+      let path = temp_dir.path().join("dist").join("foo.js");
+      fs::write(&path, "").unwrap();
+      let filter = Filter {
+        ignore: vec![glob::Pattern::new("dist/**").unwrap()],
+        gitignores: vec![],
+      };
+      let have = filter.relevant_file(temp_dir.path(), &path);
+      assert_eq!(have, None);
+    }
+
+    #[test]
+    fn ignored_by_gitignore() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      fs::write(temp_dir.path().join(".gitignore"), "target/\n").unwrap();
+      fs::create_dir(temp_dir.path().join("target")).unwrap();
+      let path = temp_dir.path().join("target").join("foo.o");
+      fs::write(&path, "").unwrap();
+      let filter = Filter {
+        ignore: vec![],
+        gitignores: load_gitignores(temp_dir.path()).unwrap(),
+      };
+      let have = filter.relevant_file(temp_dir.path(), &path);
+      assert_eq!(have, None);
+    }
+
+    #[test]
+    fn ignored_by_nested_gitignore() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      let web = temp_dir.path().join("web");
+      fs::create_dir(&web).unwrap();
+      fs::write(web.join(".gitignore"), "/build/\n").unwrap();
+      fs::create_dir(web.join("build")).unwrap();
+      let ignored = web.join("build").join("app.js");
+      fs::write(&ignored, "").unwrap();
+      // the pattern in web/.gitignore applies relative to the web directory
+      fs::create_dir(temp_dir.path().join("build")).unwrap();
+      let relevant = temp_dir.path().join("build").join("main.rs");
+      fs::write(&relevant, "").unwrap();
+      let filter = Filter {
+        ignore: vec![],
+        gitignores: load_gitignores(temp_dir.path()).unwrap(),
+      };
+      assert_eq!(filter.relevant_file(temp_dir.path(), &ignored), None);
+      assert_eq!(filter.relevant_file(temp_dir.path(), &relevant), Some(S("build/main.rs")));
+    }
+
+    #[test]
+    fn nested_gitignore_reincludes_file() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      fs::write(temp_dir.path().join(".gitignore"), "*.gen.rs\n").unwrap();
+      let sub = temp_dir.path().join("sub");
+      fs::create_dir(&sub).unwrap();
+      fs::write(sub.join(".gitignore"), "!keep.gen.rs\n").unwrap();
+      let path = sub.join("keep.gen.rs");
+      fs::write(&path, "").unwrap();
+      let filter = Filter {
+        ignore: vec![],
+        gitignores: load_gitignores(temp_dir.path()).unwrap(),
+      };
+      let have = filter.relevant_file(temp_dir.path(), &path);
+      assert_eq!(have, Some(S("sub/keep.gen.rs")));
+    }
+
+    #[test]
+    fn git_directory() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      fs::create_dir(temp_dir.path().join(".git")).unwrap();
+      let path = temp_dir.path().join(".git").join("index");
+      fs::write(&path, "").unwrap();
+      let filter = Filter {
+        ignore: vec![],
+        gitignores: vec![],
+      };
+      let have = filter.relevant_file(temp_dir.path(), &path);
+      assert_eq!(have, None);
+    }
+  }
+}
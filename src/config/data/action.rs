@@ -1,13 +1,32 @@
-use super::{Pattern, Var};
-use crate::UserError;
+use super::{ParallelOutput, Pattern, Shell, Var, parse_timeout};
 use crate::config::file::{ActionType, FileAction};
+use crate::{UserError, template};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 /// Actions are executed when receiving a command.
 #[derive(Debug, PartialEq)]
 pub struct Action {
   pub pattern: Pattern,
-  pub run: String,
+  /// the commands to run one after the other, usually just one
+  pub run: Vec<String>,
   pub vars: Vec<Var>,
+  pub timeout: Option<Duration>,
+  pub shell: Option<Shell>,
+  /// the directory in which to run the command, can contain placeholders
+  pub cwd: Option<String>,
+  /// additional environment variables, the values can contain placeholders
+  pub env: BTreeMap<String, String>,
+  /// human-readable description of this action
+  pub comment: Option<String>,
+  /// whether to use the "test-file" or "test-all" action if the variables of this action find no match
+  pub fallback: bool,
+  /// whether to run the remaining steps after a step fails
+  pub continue_on_error: bool,
+  /// whether to run all steps at the same time
+  pub parallel: bool,
+  /// how parallel steps show their output
+  pub parallel_output: ParallelOutput,
 }
 
 impl TryFrom<FileAction> for Action {
@@ -16,45 +35,78 @@ impl TryFrom<FileAction> for Action {
   fn try_from(value: FileAction) -> Result<Self, Self::Error> {
     let file_vars = value.vars.unwrap_or_default();
     let mut vars: Vec<Var> = Vec::with_capacity(file_vars.len());
-    if value.run.is_empty() {
+    let run: Vec<String> = value.run.into();
+    if run.is_empty() || run.iter().any(String::is_empty) {
       return Err(UserError::RunCommandIsEmpty);
     }
     for file_var in file_vars {
       vars.push(Var::try_from(file_var)?);
     }
-    if value.r#type == ActionType::TestAll {
-      return Ok(Action {
-        pattern: Pattern::TestAll,
-        run: value.run,
-        vars,
-      });
+    let timeout = value.timeout.map(parse_timeout).transpose()?;
+    let shell = value.shell.map(Shell::try_from).transpose()?;
+    let comment = value.comment;
+    let cwd = value.cwd;
+    let env = value.env.unwrap_or_default();
+    let fallback = value.fallback.unwrap_or_default();
+    if fallback && value.r#type != ActionType::TestFileLine {
+      return Err(UserError::FallbackRequiresTestFileLine);
     }
-    let Some(files) = value.files else {
-      return Err(UserError::MissingFilesInPattern);
-    };
-    if files.is_empty() {
-      return Err(UserError::FilesIsEmpty);
+    let continue_on_error = value.continue_on_error.unwrap_or_default();
+    let parallel = value.parallel.unwrap_or_default();
+    let parallel_output = value.parallel_output.unwrap_or_default();
+    for step in &run {
+      template::parse(step)?;
     }
-    let pattern = glob::Pattern::new(&files).map_err(|err| UserError::ConfigInvalidGlob {
-      pattern: files,
-      err: err.to_string(),
-    })?;
-    if value.r#type == ActionType::TestFile {
-      return Ok(Action {
-        pattern: Pattern::TestFile { files: pattern },
-        run: value.run,
-        vars,
-      });
+    if let Some(cwd) = &cwd {
+      template::parse(cwd)?;
     }
-    if value.r#type == ActionType::TestFileLine {
-      return Ok(Action {
-        pattern: Pattern::TestFileLine { files: pattern },
-        run: value.run,
-        vars,
-      });
+    for env_value in env.values() {
+      template::parse(env_value)?;
     }
-    Err(UserError::UnknownActionType { action_type: value.r#type })
+    let pattern = match value.r#type {
+      ActionType::TestAll => Pattern::TestAll,
+      ActionType::TestFile => Pattern::TestFile {
+        files: glob_pattern(value.files, UserError::MissingFilesInPattern, UserError::FilesIsEmpty)?,
+      },
+      ActionType::TestFileLine => Pattern::TestFileLine {
+        files: glob_pattern(value.files, UserError::MissingFilesInPattern, UserError::FilesIsEmpty)?,
+      },
+      ActionType::TestFileRange => Pattern::TestFileRange {
+        files: glob_pattern(value.files, UserError::MissingFilesInPattern, UserError::FilesIsEmpty)?,
+      },
+      ActionType::TestDir => Pattern::TestDir {
+        dirs: glob_pattern(value.dirs, UserError::MissingDirsInPattern, UserError::DirsIsEmpty)?,
+      },
+    };
+    Ok(Action {
+      pattern,
+      run,
+      vars,
+      timeout,
+      shell,
+      cwd,
+      env,
+      comment,
+      fallback,
+      continue_on_error,
+      parallel,
+      parallel_output,
+    })
+  }
+}
+
+/// parses the given glob expression of an action, fails with the given errors if it is missing or empty
+fn glob_pattern(text: Option<String>, missing: UserError, empty: UserError) -> Result<glob::Pattern, UserError> {
+  let Some(text) = text else {
+    return Err(missing);
+  };
+  if text.is_empty() {
+    return Err(empty);
   }
+  glob::Pattern::new(&text).map_err(|err| UserError::ConfigInvalidGlob {
+    pattern: text,
+    err: err.to_string(),
+  })
 }
 
 #[cfg(test)]
@@ -64,24 +116,44 @@ mod tests {
 
     mod test_all {
       use super::super::super::FileAction;
-      use crate::config::file::ActionType;
-      use crate::config::{Action, Pattern};
+      use crate::UserError;
+      use crate::config::file::{ActionType, FileRun};
+      use crate::config::{Action, ParallelOutput, Pattern};
       use big_s::S;
+      use std::collections::BTreeMap;
 
       #[test]
       fn valid() {
         let file_action = FileAction {
           r#type: ActionType::TestAll,
           files: None,
-          run: S("make test"),
+          dirs: None,
+          run: FileRun::Single(S("make test")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action).unwrap();
         let want = Action {
           pattern: Pattern::TestAll,
-          run: S("make test"),
+          run: vec![S("make test")],
           vars: vec![],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: false,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
         };
         assert_eq!(have, want);
       }
@@ -91,37 +163,172 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestAll,
           files: None,
-          run: S(""),
+          dirs: None,
+          run: FileRun::Single(S("")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action);
         assert!(have.is_err());
       }
+
+      #[test]
+      fn steps() {
+        let file_action = FileAction {
+          r#type: ActionType::TestAll,
+          files: None,
+          dirs: None,
+          run: FileRun::Steps(vec![S("make lint"), S("make test")]),
+          vars: None,
+          comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: Some(true),
+          parallel: None,
+          parallel_output: None,
+        };
+        let have = Action::try_from(file_action).unwrap();
+        let want = Action {
+          pattern: Pattern::TestAll,
+          run: vec![S("make lint"), S("make test")],
+          vars: vec![],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: true,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
+        };
+        assert_eq!(have, want);
+      }
+
+      #[test]
+      fn empty_step() {
+        let file_action = FileAction {
+          r#type: ActionType::TestAll,
+          files: None,
+          dirs: None,
+          run: FileRun::Steps(vec![S("make lint"), S("")]),
+          vars: None,
+          comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
+        };
+        let have = Action::try_from(file_action);
+        assert_eq!(have, Err(UserError::RunCommandIsEmpty));
+      }
+
+      #[test]
+      fn fallback() {
+        let file_action = FileAction {
+          r#type: ActionType::TestAll,
+          files: None,
+          dirs: None,
+          run: FileRun::Single(S("make test")),
+          vars: None,
+          comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: Some(true),
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
+        };
+        let have = Action::try_from(file_action);
+        assert_eq!(have, Err(UserError::FallbackRequiresTestFileLine));
+      }
+
+      #[test]
+      fn invalid_template() {
+        let file_action = FileAction {
+          r#type: ActionType::TestAll,
+          files: None,
+          dirs: None,
+          run: FileRun::Single(S("make {{ target | zonk }}")),
+          vars: None,
+          comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
+        };
+        let have = Action::try_from(file_action);
+        let want = Err(UserError::TemplateSyntax {
+          template: S("make {{ target | zonk }}"),
+          column: 18,
+          problem: S("unknown filter: zonk"),
+        });
+        assert_eq!(have, want);
+      }
     }
 
     mod test_file {
       use super::super::super::FileAction;
-      use crate::config::file::{ActionType, FileVar};
-      use crate::config::{Action, Pattern, Var, VarSource};
+      use crate::config::file::{ActionType, FileRun, FileVar};
+      use crate::config::{Action, ParallelOutput, Pattern, Var, VarSource};
       use big_s::S;
+      use std::collections::BTreeMap;
 
       #[test]
       fn valid_simple() {
         let file_action = FileAction {
           r#type: ActionType::TestFile,
           files: Some(S("**/*.rs")),
-          run: S("cargo test"),
+          dirs: None,
+          run: FileRun::Single(S("cargo test")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action).unwrap();
         let want = Action {
           pattern: Pattern::TestFile {
             files: glob::Pattern::new("**/*.rs").unwrap(),
           },
-          run: S("cargo test"),
+          run: vec![S("cargo test")],
           vars: vec![],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: false,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
         };
         assert_eq!(have, want);
       }
@@ -131,25 +338,52 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestFile,
           files: Some(S("**/*.rs")),
-          run: S("cargo test {{ my_var }}"),
+          dirs: None,
+          run: FileRun::Single(S("cargo test {{ my_var }}")),
           vars: Some(vec![FileVar {
             name: S("my_var"),
             source: VarSource::File,
             filter: S("^fn (.*) \\{"),
+            separator: None,
+            command: None,
+            timeout: None,
+            markers: None,
           }]),
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action).unwrap();
         let want = Action {
           pattern: Pattern::TestFile {
             files: glob::Pattern::new("**/*.rs").unwrap(),
           },
-          run: S("cargo test {{ my_var }}"),
+          run: vec![S("cargo test {{ my_var }}")],
           vars: vec![Var {
             name: S("my_var"),
             source: VarSource::File,
             filter: regex::Regex::new("^fn (.*) \\{").unwrap(),
+            separator: None,
+            group_placeholders: vec![],
+            command: None,
+            timeout: None,
+            markers: vec![],
           }],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: false,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
         };
         assert_eq!(have, want);
       }
@@ -159,9 +393,18 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestFile,
           files: None,
-          run: S("make test"),
+          dirs: None,
+          run: FileRun::Single(S("make test")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action);
         assert!(have.is_err());
@@ -172,9 +415,18 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestFile,
           files: Some(S("")),
-          run: S("make test"),
+          dirs: None,
+          run: FileRun::Single(S("make test")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action);
         assert!(have.is_err());
@@ -185,9 +437,18 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestFile,
           files: Some(S("**/*.rs")),
-          run: S(""),
+          dirs: None,
+          run: FileRun::Single(S("")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action);
         assert!(have.is_err());
@@ -196,26 +457,45 @@ mod tests {
 
     mod test_function {
       use super::super::super::FileAction;
-      use crate::config::file::{ActionType, FileVar};
-      use crate::config::{Action, Pattern, Var, VarSource};
+      use crate::config::file::{ActionType, FileRun, FileVar};
+      use crate::config::{Action, ParallelOutput, Pattern, Var, VarSource};
       use big_s::S;
+      use std::collections::BTreeMap;
 
       #[test]
       fn valid_simple() {
         let file_action = FileAction {
           r#type: ActionType::TestFileLine,
           files: Some(S("**/*.rs")),
-          run: S("cargo test"),
+          dirs: None,
+          run: FileRun::Single(S("cargo test")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action).unwrap();
         let want = Action {
           pattern: Pattern::TestFileLine {
             files: glob::Pattern::new("**/*.rs").unwrap(),
           },
-          run: S("cargo test"),
+          run: vec![S("cargo test")],
           vars: vec![],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: false,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
         };
         assert_eq!(have, want);
       }
@@ -225,25 +505,52 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestFileLine,
           files: Some(S("**/*.rs")),
-          run: S("cargo test {{ my_var }}"),
+          dirs: None,
+          run: FileRun::Single(S("cargo test {{ my_var }}")),
           vars: Some(vec![FileVar {
             name: S("my_var"),
             source: VarSource::File,
             filter: S("^fn (.*) \\{"),
+            separator: None,
+            command: None,
+            timeout: None,
+            markers: None,
           }]),
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action).unwrap();
         let want = Action {
           pattern: Pattern::TestFileLine {
             files: glob::Pattern::new("**/*.rs").unwrap(),
           },
-          run: S("cargo test {{ my_var }}"),
+          run: vec![S("cargo test {{ my_var }}")],
           vars: vec![Var {
             name: S("my_var"),
             source: VarSource::File,
             filter: regex::Regex::new("^fn (.*) \\{").unwrap(),
+            separator: None,
+            group_placeholders: vec![],
+            command: None,
+            timeout: None,
+            markers: vec![],
           }],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: false,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
         };
         assert_eq!(have, want);
       }
@@ -253,9 +560,18 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestFileLine,
           files: None,
-          run: S("make test"),
+          dirs: None,
+          run: FileRun::Single(S("make test")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action);
         assert!(have.is_err());
@@ -266,9 +582,18 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestFileLine,
           files: Some(S("")),
-          run: S("make test"),
+          dirs: None,
+          run: FileRun::Single(S("make test")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action);
         assert!(have.is_err());
@@ -279,13 +604,84 @@ mod tests {
         let file_action = FileAction {
           r#type: ActionType::TestFileLine,
           files: Some(S("**/*.rs")),
-          run: S(""),
+          dirs: None,
+          run: FileRun::Single(S("")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         };
         let have = Action::try_from(file_action);
         assert!(have.is_err());
       }
     }
+
+    mod test_dir {
+      use super::super::super::FileAction;
+      use crate::UserError;
+      use crate::config::file::{ActionType, FileRun};
+      use crate::config::{Action, ParallelOutput, Pattern};
+      use big_s::S;
+      use std::collections::BTreeMap;
+
+      fn file_action(dirs: Option<&str>) -> FileAction {
+        FileAction {
+          r#type: ActionType::TestDir,
+          files: None,
+          dirs: dirs.map(str::to_owned),
+          run: FileRun::Single(S("go test ./{{dir}}")),
+          vars: None,
+          comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
+        }
+      }
+
+      #[test]
+      fn valid() {
+        let have = Action::try_from(file_action(Some("pkg/**"))).unwrap();
+        let want = Action {
+          pattern: Pattern::TestDir {
+            dirs: glob::Pattern::new("pkg/**").unwrap(),
+          },
+          run: vec![S("go test ./{{dir}}")],
+          vars: vec![],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: false,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
+        };
+        assert_eq!(have, want);
+      }
+
+      #[test]
+      fn missing_dirs() {
+        let have = Action::try_from(file_action(None));
+        assert_eq!(have, Err(UserError::MissingDirsInPattern));
+      }
+
+      #[test]
+      fn empty_dirs() {
+        let have = Action::try_from(file_action(Some("")));
+        assert_eq!(have, Err(UserError::DirsIsEmpty));
+      }
+    }
   }
 }
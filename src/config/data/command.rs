@@ -0,0 +1,39 @@
+use super::{ParallelOutput, Shell};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+/// a fully resolved command that is ready to be executed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Command {
+  /// the commands to execute one after the other, usually just one
+  pub run: Vec<String>,
+  /// whether to execute the remaining commands after one fails
+  pub continue_on_error: bool,
+  /// whether to execute all commands at the same time
+  pub parallel: bool,
+  /// how commands that execute at the same time show their output
+  pub parallel_output: ParallelOutput,
+  /// how long the command may run before it gets stopped
+  pub timeout: Option<Duration>,
+  /// the shell that executes the command, executes the command directly if not set
+  pub shell: Option<Shell>,
+  /// the directory in which to execute the command, the current directory if not set
+  pub cwd: Option<String>,
+  /// additional environment variables for the command
+  pub env: BTreeMap<String, String>,
+}
+
+impl Display for Command {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // separate the steps the way a shell would run them
+    let separator = if self.parallel {
+      " & "
+    } else if self.continue_on_error {
+      " ; "
+    } else {
+      " && "
+    };
+    f.write_str(&self.run.join(separator))
+  }
+}
@@ -1,12 +1,15 @@
-use super::{Action, Options};
+use super::{Action, Command, CommandOutputs, Options, ParallelOutput, Var, VarSource};
 use crate::client::Trigger;
 use crate::config::file::FileConfiguration;
 use crate::{Result, UserError, template};
 use ahash::AHashMap;
+use big_s::S;
 use prettytable::Table;
 use prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR;
+use std::collections::BTreeMap;
 use std::fmt::{self, Display};
-use std::{fs, io};
+use std::path::{Component, Path, PathBuf};
+use std::{env, fs};
 
 /// filename of the Contest config file
 pub const JSON_PATH: &str = "contest.json";
@@ -15,6 +18,8 @@ pub const JSON_PATH: &str = "contest.json";
 pub struct Configuration {
   pub actions: Vec<Action>,
   pub options: Options,
+  /// the file from which this configuration was loaded
+  pub file: Option<PathBuf>,
 }
 
 impl Configuration {
@@ -56,36 +61,189 @@ impl Configuration {
     Ok(())
   }
 
-  pub fn get_command(&self, trigger: &Trigger, last_command: &mut Option<String>) -> Result<String> {
+  pub fn get_command(&self, trigger: &Trigger, last_command: &mut Option<Command>) -> Result<Command> {
     if trigger == &Trigger::RepeatLastTest {
       match last_command {
         Some(command) => return Ok(command.to_owned()),
         None => return Err(UserError::NoCommandToRepeat {}),
       }
     }
-    if let Trigger::CustomCommand { run: command } = trigger {
-      return Ok(command.to_owned());
+    if let Trigger::CustomCommand { run } = trigger {
+      return Ok(Command {
+        run: vec![run.to_owned()],
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::default(),
+        timeout: self.options.timeout,
+        shell: self.options.shell.clone(),
+        cwd: None,
+        env: self.options.env.clone(),
+      });
     }
-    for action in &self.actions {
-      if action.pattern.matches_trigger(trigger) {
-        return format_run(action, trigger);
+    self.resolve(trigger, Lookup::Run)
+  }
+
+  /// provides the command of the first action that matches the given trigger, falling back to broader actions if enabled
+  fn resolve(&self, trigger: &Trigger, lookup: Lookup) -> Result<Command> {
+    for (index, action) in self.actions.iter().enumerate() {
+      if !action.pattern.matches_trigger(trigger) {
+        if lookup == Lookup::Explain {
+          println!("actions[{index}] {}: no match", action.pattern);
+        }
+        continue;
       }
+      if lookup == Lookup::Explain {
+        println!("actions[{index}] {}: match", action.pattern);
+      }
+      let values = match placeholder_values(action, trigger, self.dir(), lookup) {
+        Ok(values) => values,
+        Err(err) if action.fallback && err.is_no_match() => return self.fallback_command(trigger, err, lookup),
+        Err(err) => return Err(err),
+      };
+      return self.render_command(action, &values);
     }
     Err(UserError::UnknownTrigger { source: trigger.to_string() })
   }
 
-  pub fn read() -> Result<Configuration> {
-    let file_content = match fs::read_to_string(JSON_PATH) {
-      Ok(file) => file,
-      Err(err) if err.kind() == io::ErrorKind::NotFound => match fs::read_to_string(JSON_PATH) {
-        Ok(file) => file,
-        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Configuration::default()),
-        Err(err) => return Err(UserError::ConfigFileError { err: err.to_string() }),
-      },
-      Err(err) => return Err(UserError::ConfigFileError { err: err.to_string() }),
+  /// provides the command that the given action runs with the given placeholder values
+  fn render_command(&self, action: &Action, values: &AHashMap<&str, String>) -> Result<Command> {
+    let mut unresolved: Vec<String> = vec![];
+    let mut render = |text: &str| -> Result<String> {
+      let rendered = template::replace_all(text, values)?;
+      for name in rendered.unresolved {
+        if !unresolved.contains(&name) {
+          unresolved.push(name);
+        }
+      }
+      Ok(rendered.text)
+    };
+    // the environment variables of the action override the global ones
+    let mut env = BTreeMap::new();
+    for (name, value) in self.options.env.iter().chain(&action.env) {
+      env.insert(name.to_owned(), render(value)?);
+    }
+    let run = action.run.iter().map(|step| render(step)).collect::<Result<Vec<String>>>()?;
+    let cwd = action.cwd.as_deref().map(&mut render).transpose()?;
+    if self.options.strict_placeholders && !unresolved.is_empty() {
+      return Err(UserError::UnresolvedPlaceholders {
+        names: unresolved,
+        comment: action.comment.clone(),
+      });
+    }
+    Ok(Command {
+      run,
+      continue_on_error: action.continue_on_error,
+      parallel: action.parallel,
+      parallel_output: action.parallel_output,
+      timeout: action.timeout.or(self.options.timeout),
+      shell: action.shell.clone().or_else(|| self.options.shell.clone()),
+      cwd,
+      env,
+    })
+  }
+
+  /// provides the command of the "test-file" action for the file of the given trigger, or else of the "test-all" action,
+  /// after the action for the given trigger failed with the given error
+  fn fallback_command(&self, trigger: &Trigger, err: UserError, lookup: Lookup) -> Result<Command> {
+    let Trigger::TestFileLine { file, line: _ } = trigger else {
+      return Err(err);
     };
+    let (reason, _) = err.messages();
+    match self.resolve(&Trigger::TestFile { file: file.to_owned() }, lookup) {
+      Ok(command) => {
+        println!("{reason}, falling back to test-file");
+        Ok(command)
+      }
+      Err(err) if err.is_no_match() || matches!(err, UserError::UnknownTrigger { source: _ }) => {
+        println!("{reason}, falling back to test-all");
+        self.resolve(&Trigger::TestAll, lookup)
+      }
+      Err(err) => Err(err),
+    }
+  }
+
+  /// prints how the given trigger resolves into a command, without executing anything
+  pub fn explain(&self, trigger: &Trigger) -> Result<()> {
+    match trigger {
+      Trigger::RepeatLastTest => {
+        println!("repeats the last executed command");
+        return Ok(());
+      }
+      Trigger::Quit => {
+        println!("stops Contest");
+        return Ok(());
+      }
+      Trigger::CustomCommand { run } => {
+        println!("command: {run}");
+        return Ok(());
+      }
+      Trigger::TestAll
+      | Trigger::TestFile { file: _ }
+      | Trigger::TestFileLine { file: _, line: _ }
+      | Trigger::TestFileRange { file: _, line: _, end_line: _ }
+      | Trigger::TestDir { dir: _ } => {}
+    }
+    let command = self.resolve(trigger, Lookup::Explain)?;
+    match command.run.as_slice() {
+      [run] => println!("command: {run}"),
+      steps => {
+        for (index, step) in steps.iter().enumerate() {
+          println!("step {}: {step}", index + 1);
+        }
+      }
+    }
+    if let Some(shell) = &command.shell {
+      println!("shell: {shell}");
+    }
+    if let Some(cwd) = &command.cwd {
+      println!("cwd: {cwd}");
+    }
+    for (name, value) in &command.env {
+      println!("env: {name}={value}");
+    }
+    Ok(())
+  }
+
+  /// provides the trigger to run when the given file has changed
+  #[must_use]
+  pub fn watch_trigger(&self, file: &str) -> Option<Trigger> {
+    let trigger = Trigger::TestFile { file: file.to_owned() };
+    if self.actions.iter().any(|action| action.pattern.matches_trigger(&trigger)) {
+      return Some(trigger);
+    }
+    if self.options.watch.fallback_to_test_all {
+      return Some(Trigger::TestAll);
+    }
+    None
+  }
+
+  /// loads the configuration file in the given directory or the closest parent directory
+  pub fn read(dir: &Path) -> Result<Configuration> {
+    match Configuration::find(dir) {
+      Some(path) => Configuration::load(&path),
+      None => Ok(Configuration::default()),
+    }
+  }
+
+  /// loads the configuration file at the given path
+  pub fn load(path: &Path) -> Result<Configuration> {
+    let file_content = fs::read_to_string(path).map_err(|err| UserError::ConfigFileError { err: err.to_string() })?;
     let file_data: FileConfiguration = json5::from_str(&file_content).map_err(|err| UserError::ConfigFileInvalidContent { err: err.to_string() })?;
-    Configuration::try_from(file_data)
+    let mut config = Configuration::try_from(file_data)?;
+    config.file = Some(path.to_path_buf());
+    Ok(config)
+  }
+
+  /// provides the path of the configuration file in the given directory or the closest parent directory
+  #[must_use]
+  pub fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().map(|ancestor| ancestor.join(JSON_PATH)).find(|path| path.is_file())
+  }
+
+  /// provides the directory that contains the configuration file
+  #[must_use]
+  pub fn dir(&self) -> Option<&Path> {
+    self.file.as_deref().and_then(Path::parent)
   }
 }
 
@@ -96,7 +254,7 @@ impl Display for Configuration {
     table.set_format(*FORMAT_NO_BORDER_LINE_SEPARATOR);
     table.add_row(prettytable::row!["TRIGGER", "RUN"]);
     for action in &self.actions {
-      table.add_row(prettytable::row![action.pattern, action.run]);
+      table.add_row(prettytable::row![action.pattern, action.run.join("\n")]);
     }
     table.printstd();
     f.write_str("Options:\n")?;
@@ -116,34 +274,135 @@ impl TryFrom<FileConfiguration> for Configuration {
     }
     Ok(Configuration {
       actions,
-      options: Options::from(value.options.unwrap_or_default()),
+      options: Options::try_from(value.options.unwrap_or_default())?,
+      file: None,
     })
   }
 }
 
-/// replaces all placeholders in the given run string
-fn format_run(action: &Action, trigger: &Trigger) -> Result<String> {
+/// provides the values of all placeholders in the given action for the given trigger
+fn placeholder_values<'a>(action: &'a Action, trigger: &Trigger, config_dir: Option<&Path>, lookup: Lookup) -> Result<AHashMap<&'a str, String>> {
+  let mut values = trigger_values(trigger, config_dir);
+  if lookup == Lookup::Explain {
+    let mut names: Vec<&str> = values.keys().copied().collect();
+    names.sort_unstable();
+    for name in names {
+      println!("  {name} = {}", values[name]);
+    }
+  }
+  let mut outputs = CommandOutputs::new();
+  for var in &action.vars {
+    match lookup {
+      Lookup::Run => var.insert_into(&mut values, &mut outputs)?,
+      Lookup::Explain => explain_var(var, &mut values, &mut outputs)?,
+    }
+  }
+  Ok(values)
+}
+
+/// prints the value of the given variable and adds it to the given placeholder values.
+/// Describes the command of "command" variables instead of running it.
+fn explain_var<'a>(var: &'a Var, values: &mut AHashMap<&'a str, String>, outputs: &mut CommandOutputs) -> Result<()> {
+  if var.source == VarSource::Command {
+    let command = template::replace_all(var.command.as_deref().unwrap_or_default(), values)?.text;
+    let description = format!("<command: {command}>");
+    println!("  {} = {description}", var.name);
+    for placeholder in &var.group_placeholders {
+      values.insert(placeholder, description.clone());
+    }
+    values.insert(&var.name, description);
+    return Ok(());
+  }
+  let var_value = var.calculate(values, outputs)?;
+  let shown = var_value.value.replace(template::LIST_SEPARATOR, template::DEFAULT_LIST_JOIN);
+  match var_value.line {
+    Some(line) => println!("  {} = {shown} (found on line {line})", var.name),
+    None => println!("  {} = {shown}", var.name),
+  }
+  for (placeholder, value) in var.group_placeholders.iter().zip(var_value.groups) {
+    println!("  {placeholder} = {value}");
+    values.insert(placeholder, value);
+  }
+  values.insert(&var.name, var_value.value);
+  Ok(())
+}
+
+/// how to resolve a trigger into a command
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Lookup {
+  /// determine the command to run
+  Run,
+  /// also print each step of the lookup, without running the commands of "command" variables
+  Explain,
+}
+
+/// provides the values of the placeholders that the given trigger defines
+fn trigger_values(trigger: &Trigger, config_dir: Option<&Path>) -> AHashMap<&'static str, String> {
   let mut values: AHashMap<&str, String> = AHashMap::new();
   if let Trigger::TestFile { file } = &trigger {
-    values.insert("file", file.to_owned());
+    values.extend(file_values(file, config_dir));
   }
   if let Trigger::TestFileLine { file, line } = &trigger {
-    values.insert("file", file.to_owned());
+    values.extend(file_values(file, config_dir));
     values.insert("line", line.to_string());
   }
-  for var in &action.vars {
-    values.insert(&var.name, var.calculate_var(&values)?);
+  if let Trigger::TestFileRange { file, line, end_line } = &trigger {
+    values.extend(file_values(file, config_dir));
+    values.insert("line", line.to_string());
+    values.insert("end_line", end_line.to_string());
+  }
+  if let Trigger::TestDir { dir } = &trigger {
+    values.insert("dir", dir.to_owned());
   }
-  template::replace_all(&action.run, &values)
+  values
+}
+
+/// provides the placeholders derived from the path of the given file
+fn file_values(file: &str, config_dir: Option<&Path>) -> Vec<(&'static str, String)> {
+  let path = Path::new(file);
+  let text = |value: Option<&std::ffi::OsStr>| value.map(|value| value.to_string_lossy().to_string()).unwrap_or_default();
+  let file_dir = match path.parent() {
+    Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().to_string(),
+    _ => S("."),
+  };
+  let file_abs = if path.is_absolute() {
+    path.to_path_buf()
+  } else {
+    env::current_dir().map_or_else(|_| path.to_path_buf(), |current_dir| current_dir.join(path))
+  };
+  let rel_to_config = match config_dir {
+    Some(config_dir) => file_abs.strip_prefix(config_dir).unwrap_or(path),
+    None => path,
+  };
+  let module: Vec<String> = rel_to_config
+    .with_extension("")
+    .components()
+    .filter_map(|component| match component {
+      Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+      _ => None,
+    })
+    .collect();
+  vec![
+    ("file", file.to_owned()),
+    ("file_name", text(path.file_name())),
+    ("file_stem", text(path.file_stem())),
+    ("file_ext", text(path.extension())),
+    ("file_dir", file_dir),
+    ("file_abs", file_abs.to_string_lossy().to_string()),
+    ("file_rel_to_config", rel_to_config.to_string_lossy().to_string()),
+    ("file_module_dots", module.join(".")),
+    ("file_module_colons", module.join("::")),
+  ]
 }
 
 #[cfg(test)]
 mod tests {
 
   mod try_from {
-    use crate::config::file::{ActionType, FileAction, FileConfiguration};
-    use crate::config::{Action, Configuration, Options, Pattern};
+    use crate::config::file::{ActionType, FileAction, FileConfiguration, FileRun};
+    use crate::config::{Action, Configuration, Options, ParallelOutput, Pattern};
     use big_s::S;
+    use std::collections::BTreeMap;
 
     #[test]
     fn simple() {
@@ -151,9 +410,18 @@ mod tests {
         actions: vec![FileAction {
           r#type: ActionType::TestFile,
           files: Some(S("*.rs")),
-          run: S("make test"),
+          dirs: None,
+          run: FileRun::Single(S("make test")),
           vars: None,
           comment: None,
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: None,
+          fallback: None,
+          continue_on_error: None,
+          parallel: None,
+          parallel_output: None,
         }],
         options: None,
       };
@@ -163,21 +431,121 @@ mod tests {
           pattern: Pattern::TestFile {
             files: glob::Pattern::new("*.rs").unwrap(),
           },
-          run: S("make test"),
+          run: vec![S("make test")],
           vars: vec![],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: false,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
         }],
         options: Options::default(),
+        file: None,
       };
       assert_eq!(have, want);
     }
   }
 
+  mod find {
+    use super::super::{Configuration, JSON_PATH};
+    use std::fs;
+
+    #[test]
+    fn in_given_dir() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      let config_path = temp_dir.path().join(JSON_PATH);
+      fs::write(&config_path, "{}").unwrap();
+      let have = Configuration::find(temp_dir.path());
+      assert_eq!(have, Some(config_path));
+    }
+
+    #[test]
+    fn in_parent_dir() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      let config_path = temp_dir.path().join(JSON_PATH);
+      fs::write(&config_path, "{}").unwrap();
+      let sub_dir = temp_dir.path().join("sub").join("dir");
+      fs::create_dir_all(&sub_dir).unwrap();
+      let have = Configuration::find(&sub_dir);
+      assert_eq!(have, Some(config_path));
+    }
+
+    #[test]
+    fn nearest_wins() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      fs::write(temp_dir.path().join(JSON_PATH), "{}").unwrap();
+      let sub_dir = temp_dir.path().join("sub");
+      fs::create_dir(&sub_dir).unwrap();
+      let config_path = sub_dir.join(JSON_PATH);
+      fs::write(&config_path, "{}").unwrap();
+      let have = Configuration::find(&sub_dir);
+      assert_eq!(have, Some(config_path));
+    }
+  }
+
+  mod file_values {
+    use super::super::file_values;
+    use crate::config::data::pattern::FILE_PLACEHOLDERS;
+    use ahash::AHashMap;
+    use big_s::S;
+    use std::path::Path;
+
+    #[test]
+    fn absolute_path_in_config_dir() {
+      let have: AHashMap<&str, String> = file_values("/repo/src/parser/lexer.test.ts", Some(Path::new("/repo"))).into_iter().collect();
+      let want: AHashMap<&str, String> = AHashMap::from([
+        ("file", S("/repo/src/parser/lexer.test.ts")),
+        ("file_name", S("lexer.test.ts")),
+        ("file_stem", S("lexer.test")),
+        ("file_ext", S("ts")),
+        ("file_dir", S("/repo/src/parser")),
+        ("file_abs", S("/repo/src/parser/lexer.test.ts")),
+        ("file_rel_to_config", S("src/parser/lexer.test.ts")),
+        ("file_module_dots", S("src.parser.lexer.test")),
+        ("file_module_colons", S("src::parser::lexer.test")),
+      ]);
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn relative_path_without_dir() {
+      let have: AHashMap<&str, String> = file_values("setup.py", None).into_iter().collect();
+      assert_eq!(have["file_name"], "setup.py");
+      assert_eq!(have["file_stem"], "setup");
+      assert_eq!(have["file_ext"], "py");
+      assert_eq!(have["file_dir"], ".");
+      assert!(have["file_abs"].ends_with("/setup.py"));
+      assert_eq!(have["file_rel_to_config"], "setup.py");
+      assert_eq!(have["file_module_dots"], "setup");
+    }
+
+    #[test]
+    fn provides_all_file_placeholders() {
+      let have: Vec<&str> = file_values("src/main.rs", None).into_iter().map(|(name, _)| name).collect();
+      assert_eq!(have, FILE_PLACEHOLDERS);
+    }
+
+    #[test]
+    fn no_extension() {
+      let have: AHashMap<&str, String> = file_values("bin/run", None).into_iter().collect();
+      assert_eq!(have["file_stem"], "run");
+      assert_eq!(have["file_ext"], "");
+      assert_eq!(have["file_dir"], "bin");
+      assert_eq!(have["file_module_colons"], "bin::run");
+    }
+  }
+
   #[cfg(test)]
   mod get_command {
-    use super::super::super::{Action, Configuration};
+    use super::super::super::{Action, Command, Configuration, Options, Shell};
     use super::super::*;
     use crate::config::Pattern;
     use big_s::S;
+    use std::time::Duration;
 
     #[test]
     fn exact_match() {
@@ -185,31 +553,292 @@ mod tests {
         pattern: Pattern::TestFileLine {
           files: glob::Pattern::new("filename1").unwrap(),
         },
-        run: String::from("action1 command"),
+        run: vec![String::from("action1 command")],
         vars: vec![],
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: None,
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
       };
       let action2 = Action {
         pattern: Pattern::TestFileLine {
           files: glob::Pattern::new("filename2").unwrap(),
         },
-        run: String::from("action2 command"),
+        run: vec![String::from("action2 command")],
         vars: vec![],
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: None,
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
       };
       let action3 = Action {
         pattern: Pattern::TestFileLine {
           files: glob::Pattern::new("filename3").unwrap(),
         },
-        run: String::from("action3 command"),
+        run: vec![String::from("action3 command")],
         vars: vec![],
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: None,
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
       };
       let config = Configuration {
         actions: vec![action1, action2, action3],
         ..Configuration::default()
       };
       let trigger = Trigger::TestFileLine { file: S("filename2"), line: 2 };
-      let mut last_command: Option<String> = None;
+      let mut last_command: Option<Command> = None;
       let have = config.get_command(&trigger, &mut last_command);
-      assert_eq!(have, Ok(String::from("action2 command")));
+      let want = Command {
+        run: vec![String::from("action2 command")],
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+      };
+      assert_eq!(have, Ok(want));
+    }
+
+    #[test]
+    fn steps() {
+      let action = Action {
+        pattern: Pattern::TestFile {
+          files: glob::Pattern::new("**/*.rs").unwrap(),
+        },
+        run: vec![S("cargo clippy -- {{ file }}"), S("cargo test {{ file_stem }}")],
+        vars: vec![],
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: None,
+        fallback: false,
+        continue_on_error: true,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+      };
+      let config = Configuration {
+        actions: vec![action],
+        ..Configuration::default()
+      };
+      let trigger = Trigger::TestFile { file: S("src/parser.rs") };
+      let have = config.get_command(&trigger, &mut None);
+      let want = Command {
+        run: vec![S("cargo clippy -- src/parser.rs"), S("cargo test parser")],
+        continue_on_error: true,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+      };
+      assert_eq!(have, Ok(want));
+    }
+
+    #[test]
+    fn unresolved_placeholders() {
+      let action = Action {
+        pattern: Pattern::TestAll,
+        run: vec![String::from("make {{ target }} {{ mode }} {{ target }}")],
+        vars: vec![],
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: Some(S("build")),
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+      };
+      let config = Configuration {
+        actions: vec![action],
+        ..Configuration::default()
+      };
+      let mut last_command: Option<Command> = None;
+      let have = config.get_command(&Trigger::TestAll, &mut last_command);
+      let want = Err(UserError::UnresolvedPlaceholders {
+        names: vec![S("target"), S("mode")],
+        comment: Some(S("build")),
+      });
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn unresolved_placeholders_not_strict() {
+      let action = Action {
+        pattern: Pattern::TestAll,
+        run: vec![String::from("make {{ target }}")],
+        vars: vec![],
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: None,
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+      };
+      let config = Configuration {
+        actions: vec![action],
+        options: Options {
+          strict_placeholders: false,
+          ..Options::default()
+        },
+        file: None,
+      };
+      let mut last_command: Option<Command> = None;
+      let have = config.get_command(&Trigger::TestAll, &mut last_command).unwrap();
+      assert_eq!(have.run, vec![S("make {{ target }}")]);
+    }
+
+    #[test]
+    fn timeout() {
+      let action = Action {
+        pattern: Pattern::TestAll,
+        run: vec![String::from("action command")],
+        vars: vec![],
+        timeout: Some(Duration::from_secs(10)),
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: None,
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+      };
+      let config = Configuration {
+        actions: vec![action],
+        options: Options {
+          timeout: Some(Duration::from_secs(30)),
+          ..Options::default()
+        },
+        file: None,
+      };
+      let mut last_command: Option<Command> = None;
+      let have = config.get_command(&Trigger::TestAll, &mut last_command);
+      let want = Command {
+        run: vec![String::from("action command")],
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+        timeout: Some(Duration::from_secs(10)),
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+      };
+      assert_eq!(have, Ok(want));
+    }
+
+    #[test]
+    fn default_timeout() {
+      let config = Configuration {
+        actions: vec![],
+        options: Options {
+          timeout: Some(Duration::from_secs(30)),
+          ..Options::default()
+        },
+        file: None,
+      };
+      let trigger = Trigger::CustomCommand { run: S("custom command") };
+      let mut last_command: Option<Command> = None;
+      let have = config.get_command(&trigger, &mut last_command);
+      let want = Command {
+        run: vec![S("custom command")],
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+        timeout: Some(Duration::from_secs(30)),
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+      };
+      assert_eq!(have, Ok(want));
+    }
+
+    #[test]
+    fn shell() {
+      let action = Action {
+        pattern: Pattern::TestAll,
+        run: vec![S("make test | tee test.log")],
+        vars: vec![],
+        timeout: None,
+        shell: Some(Shell::try_from(S("bash -c")).unwrap()),
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: None,
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+      };
+      let config = Configuration {
+        actions: vec![action],
+        options: Options {
+          shell: Some(Shell::try_from(S("sh -c")).unwrap()),
+          ..Options::default()
+        },
+        file: None,
+      };
+      let mut last_command: Option<Command> = None;
+      let have = config.get_command(&Trigger::TestAll, &mut last_command).unwrap();
+      assert_eq!(have.shell, Some(Shell::try_from(S("bash -c")).unwrap()));
+      let have = config.get_command(&Trigger::CustomCommand { run: S("ls | wc -l") }, &mut last_command).unwrap();
+      assert_eq!(have.shell, Some(Shell::try_from(S("sh -c")).unwrap()));
+    }
+
+    #[test]
+    fn cwd_and_env() {
+      let action = Action {
+        pattern: Pattern::TestFile {
+          files: glob::Pattern::new("**/*.rs").unwrap(),
+        },
+        run: vec![S("cargo test")],
+        vars: vec![],
+        timeout: None,
+        shell: None,
+        cwd: Some(S("{{file}}/..")),
+        env: BTreeMap::from([(S("TEST_FILE"), S("{{file}}")), (S("RUST_LOG"), S("debug"))]),
+        comment: None,
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
+      };
+      let config = Configuration {
+        actions: vec![action],
+        options: Options {
+          env: BTreeMap::from([(S("RUST_LOG"), S("info")), (S("CI"), S("true"))]),
+          ..Options::default()
+        },
+        file: None,
+      };
+      let trigger = Trigger::TestFile { file: S("src/lib.rs") };
+      let have = config.get_command(&trigger, &mut None).unwrap();
+      assert_eq!(have.cwd, Some(S("src/lib.rs/..")));
+      let want_env = BTreeMap::from([(S("CI"), S("true")), (S("RUST_LOG"), S("debug")), (S("TEST_FILE"), S("src/lib.rs"))]);
+      assert_eq!(have.env, want_env);
     }
 
     #[test]
@@ -218,15 +847,24 @@ mod tests {
         pattern: Pattern::TestFile {
           files: glob::Pattern::new("*.rs").unwrap(),
         },
-        run: String::from("action1 command"),
+        run: vec![String::from("action1 command")],
         vars: vec![],
+        timeout: None,
+        shell: None,
+        cwd: None,
+        env: BTreeMap::new(),
+        comment: None,
+        fallback: false,
+        continue_on_error: false,
+        parallel: false,
+        parallel_output: ParallelOutput::Grouped,
       };
       let config = Configuration {
         actions: vec![action1],
         ..Configuration::default()
       };
       let give = Trigger::TestFile { file: S("other_filename") };
-      let mut last_command: Option<String> = None;
+      let mut last_command: Option<Command> = None;
       let have = config.get_command(&give, &mut last_command);
       assert!(have.is_err());
     }
@@ -238,9 +876,64 @@ mod tests {
         ..Configuration::default()
       };
       let trigger = Trigger::TestAll;
-      let mut last_command: Option<String> = None;
+      let mut last_command: Option<Command> = None;
       let have = config.get_command(&trigger, &mut last_command);
       assert!(have.is_err());
     }
   }
+
+  mod watch_trigger {
+    use crate::client::Trigger;
+    use crate::config::{Action, Configuration, Options, ParallelOutput, Pattern, Watch};
+    use big_s::S;
+    use std::collections::BTreeMap;
+
+    fn config(fallback_to_test_all: bool) -> Configuration {
+      Configuration {
+        actions: vec![Action {
+          pattern: Pattern::TestFile {
+            files: glob::Pattern::new("**/*.rs").unwrap(),
+          },
+          run: vec![S("cargo test {{ file }}")],
+          vars: vec![],
+          timeout: None,
+          shell: None,
+          cwd: None,
+          env: BTreeMap::new(),
+          comment: None,
+          fallback: false,
+          continue_on_error: false,
+          parallel: false,
+          parallel_output: ParallelOutput::Grouped,
+        }],
+        options: Options {
+          watch: Watch {
+            fallback_to_test_all,
+            ..Watch::default()
+          },
+          ..Options::default()
+        },
+        file: None,
+      }
+    }
+
+    #[test]
+    fn matching_file() {
+      let have = config(false).watch_trigger("src/lib.rs");
+      let want = Some(Trigger::TestFile { file: S("src/lib.rs") });
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn mismatching_file() {
+      let have = config(false).watch_trigger("README.md");
+      assert_eq!(have, None);
+    }
+
+    #[test]
+    fn mismatching_file_with_fallback() {
+      let have = config(true).watch_trigger("README.md");
+      assert_eq!(have, Some(Trigger::TestAll));
+    }
+  }
 }
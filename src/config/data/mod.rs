@@ -1,17 +1,27 @@
 mod action;
 mod after_run;
 mod before_run;
+mod command;
 mod configuration;
 mod options;
+mod parallel_output;
 mod pattern;
+mod shell;
+mod timeout;
 mod var;
 mod var_source;
+mod watch;
 
 pub use action::Action;
 pub use after_run::AfterRun;
 pub use before_run::BeforeRun;
+pub use command::Command;
 pub use configuration::{Configuration, JSON_PATH};
 pub use options::Options;
+pub use parallel_output::ParallelOutput;
 pub use pattern::Pattern;
-pub use var::Var;
+pub use shell::Shell;
+pub use timeout::parse_timeout;
+pub use var::{CommandOutputs, Var, VarValue};
 pub use var_source::VarSource;
+pub use watch::Watch;
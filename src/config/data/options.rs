@@ -1,18 +1,60 @@
-use super::{AfterRun, BeforeRun};
+use super::{AfterRun, BeforeRun, Shell, Watch, parse_timeout};
 use crate::config::file::FileOptions;
+use crate::{UserError, template};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct Options {
   pub before_run: BeforeRun,
   pub after_run: AfterRun,
+  pub cancel_on_new_trigger: bool,
+  pub timeout: Option<Duration>,
+  pub watch: Watch,
+  /// how many past test runs to keep in the history
+  pub history: usize,
+  pub shell: Option<Shell>,
+  /// additional environment variables for all commands
+  pub env: BTreeMap<String, String>,
+  /// whether commands with unresolved placeholders fail instead of running
+  pub strict_placeholders: bool,
 }
 
-impl From<FileOptions> for Options {
-  fn from(value: FileOptions) -> Self {
-    Options {
+impl Default for Options {
+  fn default() -> Self {
+    Self {
+      before_run: BeforeRun::default(),
+      after_run: AfterRun::default(),
+      cancel_on_new_trigger: false,
+      timeout: None,
+      watch: Watch::default(),
+      history: 0,
+      shell: None,
+      env: BTreeMap::new(),
+      strict_placeholders: true,
+    }
+  }
+}
+
+impl TryFrom<FileOptions> for Options {
+  type Error = UserError;
+
+  fn try_from(value: FileOptions) -> Result<Self, Self::Error> {
+    let env = value.env.unwrap_or_default();
+    for env_value in env.values() {
+      template::parse(env_value)?;
+    }
+    Ok(Options {
       before_run: BeforeRun::from(value.before_run.unwrap_or_default()),
       after_run: AfterRun::from(value.after_run.unwrap_or_default()),
-    }
+      cancel_on_new_trigger: value.cancel_on_new_trigger.unwrap_or_default(),
+      timeout: value.timeout.map(parse_timeout).transpose()?,
+      watch: Watch::try_from(value.watch.unwrap_or_default())?,
+      history: value.history.unwrap_or(Options::default().history),
+      shell: value.shell.map(Shell::try_from).transpose()?,
+      env,
+      strict_placeholders: value.strict_placeholders.unwrap_or(Options::default().strict_placeholders),
+    })
   }
 }
 
@@ -21,16 +63,27 @@ mod tests {
 
   mod into_domain {
     use super::super::FileOptions;
-    use crate::config::file::{FileAfterRun, FileBeforeRun};
-    use crate::config::{AfterRun, BeforeRun, Options};
+    use crate::UserError;
+    use crate::config::file::{FileAfterRun, FileBeforeRun, FileWatch};
+    use crate::config::{AfterRun, BeforeRun, Options, Watch};
+    use big_s::S;
+    use std::collections::BTreeMap;
+    use std::time::Duration;
 
     #[test]
     fn empty() {
       let file_options = FileOptions {
         before_run: None,
         after_run: None,
+        cancel_on_new_trigger: None,
+        timeout: None,
+        watch: None,
+        history: None,
+        shell: None,
+        env: None,
+        strict_placeholders: None,
       };
-      let have = Options::from(file_options);
+      let have = Options::try_from(file_options).unwrap();
       let want = Options {
         before_run: BeforeRun { clear_screen: false, newlines: 0 },
         after_run: AfterRun {
@@ -38,6 +91,13 @@ mod tests {
           indicator_lines: 0,
           print_result: true,
         },
+        cancel_on_new_trigger: false,
+        timeout: None,
+        watch: Watch::default(),
+        history: 0,
+        shell: None,
+        env: BTreeMap::new(),
+        strict_placeholders: true,
       };
       assert_eq!(have, want);
     }
@@ -54,8 +114,18 @@ mod tests {
           indicator_lines: Some(6),
           print_result: Some(false),
         }),
+        cancel_on_new_trigger: Some(true),
+        timeout: Some(2.5),
+        watch: Some(FileWatch {
+          debounce: Some(200),
+          ..FileWatch::default()
+        }),
+        history: Some(5),
+        shell: None,
+        env: None,
+        strict_placeholders: None,
       };
-      let have = Options::from(file_options);
+      let have = Options::try_from(file_options).unwrap();
       let want = Options {
         before_run: BeforeRun { clear_screen: true, newlines: 2 },
         after_run: AfterRun {
@@ -63,7 +133,32 @@ mod tests {
           indicator_lines: 6,
           print_result: false,
         },
+        cancel_on_new_trigger: true,
+        timeout: Some(Duration::from_millis(2500)),
+        watch: Watch {
+          debounce: Duration::from_millis(200),
+          ..Watch::default()
+        },
+        history: 5,
+        shell: None,
+        env: BTreeMap::new(),
+        strict_placeholders: true,
+      };
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn invalid_env_template() {
+      let file_options = FileOptions {
+        env: Some(BTreeMap::from([(S("TEST_FILE"), S("{{ file | zonk }}"))])),
+        ..FileOptions::default()
       };
+      let have = Options::try_from(file_options);
+      let want = Err(UserError::TemplateSyntax {
+        template: S("{{ file | zonk }}"),
+        column: 11,
+        problem: S("unknown filter: zonk"),
+      });
       assert_eq!(have, want);
     }
   }
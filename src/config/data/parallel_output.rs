@@ -0,0 +1,24 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::fmt::{self, Display};
+
+/// how steps that run in parallel show their output
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParallelOutput {
+  /// buffer the output of each step and print it under a header once all steps have finished
+  #[default]
+  Grouped,
+  /// print the output of all steps as it arrives, with the number of the step in front of each line
+  Prefixed,
+}
+
+impl Display for ParallelOutput {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let text = match &self {
+      ParallelOutput::Grouped => "grouped",
+      ParallelOutput::Prefixed => "prefixed",
+    };
+    write!(f, "{text}")
+  }
+}
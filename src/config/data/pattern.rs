@@ -1,12 +1,27 @@
 use crate::client::Trigger;
 use std::fmt::Display;
 
+/// names of the placeholders that actions for triggers with a file provide
+pub const FILE_PLACEHOLDERS: [&str; 9] = [
+  "file",
+  "file_name",
+  "file_stem",
+  "file_ext",
+  "file_dir",
+  "file_abs",
+  "file_rel_to_config",
+  "file_module_dots",
+  "file_module_colons",
+];
+
 /// a pattern defined in the config file, describes conditions that match actions
 #[derive(Debug, Eq, PartialEq)]
 pub enum Pattern {
   TestAll,
   TestFile { files: glob::Pattern },
   TestFileLine { files: glob::Pattern },
+  TestFileRange { files: glob::Pattern },
+  TestDir { dirs: glob::Pattern },
 }
 
 impl Pattern {
@@ -25,9 +40,39 @@ impl Pattern {
           return files.matches(file);
         }
       }
+      Pattern::TestFileRange { files } => {
+        if let Trigger::TestFileRange { file, line: _, end_line: _ } = &trigger {
+          return files.matches(file);
+        }
+      }
+      Pattern::TestDir { dirs } => {
+        if let Trigger::TestDir { dir } = &trigger {
+          return dirs.matches(dir);
+        }
+      }
     }
     false
   }
+
+  /// provides the names of the placeholders that Contest provides to actions with this pattern
+  #[must_use]
+  pub fn builtin_placeholders(&self) -> Vec<&'static str> {
+    match self {
+      Pattern::TestAll => vec![],
+      Pattern::TestFile { files: _ } => FILE_PLACEHOLDERS.to_vec(),
+      Pattern::TestFileLine { files: _ } => {
+        let mut result = FILE_PLACEHOLDERS.to_vec();
+        result.push("line");
+        result
+      }
+      Pattern::TestFileRange { files: _ } => {
+        let mut result = FILE_PLACEHOLDERS.to_vec();
+        result.extend(["line", "end_line"]);
+        result
+      }
+      Pattern::TestDir { dirs: _ } => vec!["dir"],
+    }
+  }
 }
 
 impl Display for Pattern {
@@ -36,6 +81,8 @@ impl Display for Pattern {
       Pattern::TestAll => f.write_str("test-all"),
       Pattern::TestFile { files } => write!(f, "test-file {files}"),
       Pattern::TestFileLine { files } => write!(f, "test-file-line {files}"),
+      Pattern::TestFileRange { files } => write!(f, "test-file-range {files}"),
+      Pattern::TestDir { dirs } => write!(f, "test-dir {dirs}"),
     }
   }
 }
@@ -127,5 +174,37 @@ mod tests {
         assert!(!config_trigger.matches_trigger(&client_trigger));
       }
     }
+
+    mod test_dir {
+      use crate::{client, config};
+      use big_s::S;
+
+      #[test]
+      fn matches() {
+        let config_trigger = config::Pattern::TestDir {
+          dirs: glob::Pattern::new("pkg/**").unwrap(),
+        };
+        let client_trigger = client::Trigger::TestDir { dir: S("pkg/parser") };
+        assert!(config_trigger.matches_trigger(&client_trigger));
+      }
+
+      #[test]
+      fn mismatching_dir() {
+        let config_trigger = config::Pattern::TestDir {
+          dirs: glob::Pattern::new("pkg/**").unwrap(),
+        };
+        let client_trigger = client::Trigger::TestDir { dir: S("cmd/server") };
+        assert!(!config_trigger.matches_trigger(&client_trigger));
+      }
+
+      #[test]
+      fn mismatching_type() {
+        let config_trigger = config::Pattern::TestDir {
+          dirs: glob::Pattern::new("**").unwrap(),
+        };
+        let client_trigger = client::Trigger::TestFile { file: S("pkg/parser/lexer.go") };
+        assert!(!config_trigger.matches_trigger(&client_trigger));
+      }
+    }
   }
 }
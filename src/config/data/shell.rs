@@ -0,0 +1,65 @@
+use crate::UserError;
+use std::fmt::{self, Display};
+
+/// a shell that executes the rendered "run" strings, for example `sh -c`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Shell {
+  /// the shell executable
+  pub program: String,
+  /// the arguments that go before the "run" string
+  pub args: Vec<String>,
+}
+
+impl TryFrom<String> for Shell {
+  type Error = UserError;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    let words = shellwords::split(&value).map_err(|err| UserError::CannotSplitShellString {
+      source: value.clone(),
+      err: err.to_string(),
+    })?;
+    let Some((program, args)) = words.split_first() else {
+      return Err(UserError::ShellIsEmpty);
+    };
+    Ok(Shell {
+      program: program.to_owned(),
+      args: args.to_vec(),
+    })
+  }
+}
+
+impl Display for Shell {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.program)?;
+    for arg in &self.args {
+      write!(f, " {arg}")?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod try_from {
+    use super::super::Shell;
+    use crate::UserError;
+    use big_s::S;
+
+    #[test]
+    fn with_args() {
+      let have = Shell::try_from(S("bash -e -c")).unwrap();
+      let want = Shell {
+        program: S("bash"),
+        args: vec![S("-e"), S("-c")],
+      };
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn empty() {
+      let have = Shell::try_from(S(" "));
+      assert_eq!(have, Err(UserError::ShellIsEmpty));
+    }
+  }
+}
@@ -0,0 +1,34 @@
+use crate::{Result, UserError};
+use std::time::Duration;
+
+/// converts the given number of seconds from the config file into a `Duration`
+pub fn parse_timeout(seconds: f64) -> Result<Duration> {
+  Duration::try_from_secs_f64(seconds).map_err(|err| UserError::InvalidTimeout {
+    timeout: seconds.to_string(),
+    err: err.to_string(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::parse_timeout;
+  use std::time::Duration;
+
+  #[test]
+  fn whole_seconds() {
+    let have = parse_timeout(30.0).unwrap();
+    assert_eq!(have, Duration::from_secs(30));
+  }
+
+  #[test]
+  fn fractional_seconds() {
+    let have = parse_timeout(0.5).unwrap();
+    assert_eq!(have, Duration::from_millis(500));
+  }
+
+  #[test]
+  fn negative() {
+    let have = parse_timeout(-1.0);
+    assert!(have.is_err());
+  }
+}
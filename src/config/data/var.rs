@@ -1,48 +1,219 @@
-use super::VarSource;
+use super::{VarSource, parse_timeout};
 use crate::config::file::FileVar;
-use crate::{Result, UserError, scanner};
+use crate::{Result, UserError, scanner, subshell, template};
 use ahash::AHashMap;
 use regex::Regex;
+use std::path::Path;
+use std::time::Duration;
+
+/// the default text between the scopes found by the "enclosingScopes" source
+const DEFAULT_SEPARATOR: &str = "::";
+
+/// how long the command of the "command" source may run by default
+const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// the files that mark the root directory of a project if the "projectRoot" source doesn't define any
+const DEFAULT_MARKERS: [&str; 4] = ["Cargo.toml", "package.json", "go.mod", "pyproject.toml"];
+
+/// the output of the commands that variables of the "command" source have executed during a test run,
+/// keyed by the command after replacing its placeholders
+pub type CommandOutputs = AHashMap<String, String>;
 
 #[derive(Debug)]
 pub struct Var {
   pub name: String,
   pub source: VarSource,
   pub filter: regex::Regex,
+  /// joins the matches of the "enclosingScopes" source
+  pub separator: Option<String>,
+  /// the placeholders for the named capture groups in the filter, like "name.group", in the order of the groups
+  pub group_placeholders: Vec<String>,
+  /// the command that the "command" source runs
+  pub command: Option<String>,
+  /// how long the command of the "command" source may run
+  pub timeout: Option<Duration>,
+  /// the names of the files that the "projectRoot" source looks for, empty means the default markers
+  pub markers: Vec<String>,
+}
+
+/// the value of a variable and where it was found
+#[derive(Debug, Eq, PartialEq)]
+pub struct VarValue {
+  pub value: String,
+  /// the 1-based number of the line in the file content that the value was found on
+  pub line: Option<usize>,
+  /// the values of the named capture groups in the filter, in the order of the groups
+  pub groups: Vec<String>,
 }
 
 impl Var {
-  pub fn calculate_var(&self, values: &AHashMap<&str, String>) -> Result<String> {
+  /// calculates the value of this variable and adds it and the values of its named capture groups to the given placeholder values
+  pub fn insert_into<'a>(&'a self, values: &mut AHashMap<&'a str, String>, outputs: &mut CommandOutputs) -> Result<()> {
+    let var_value = self.calculate(values, outputs)?;
+    for (placeholder, value) in self.group_placeholders.iter().zip(var_value.groups) {
+      values.insert(placeholder, value);
+    }
+    values.insert(&self.name, var_value.value);
+    Ok(())
+  }
+
+  /// provides the value of this variable together with where it was found
+  pub fn calculate(&self, values: &AHashMap<&str, String>, outputs: &mut CommandOutputs) -> Result<VarValue> {
     match self.source {
       VarSource::File => {
         let Some(filename) = values.get("file") else {
           return Err(UserError::FileNameNotAvailable);
         };
-        filter(filename, &self.filter)
+        Ok(VarValue::from(filter(filename, &self.filter)?))
       }
       VarSource::Line => {
         let Some(line) = values.get("line") else {
           return Err(UserError::LineNotAvailable);
         };
-        filter(line, &self.filter)
+        Ok(VarValue::from(filter(line, &self.filter)?))
       }
       VarSource::CurrentOrAboveLineContent => {
-        let Some(filename) = values.get("file") else {
+        let (filename, original_line) = file_and_line(values)?;
+        Ok(VarValue::from(scanner::file_upwards(filename, &self.filter, original_line)?))
+      }
+      VarSource::CurrentOrBelowLineContent => {
+        let (filename, original_line) = file_and_line(values)?;
+        Ok(VarValue::from(scanner::file_downwards(filename, &self.filter, original_line)?))
+      }
+      VarSource::NearestLineContent => {
+        let (filename, original_line) = file_and_line(values)?;
+        Ok(VarValue::from(scanner::file_nearest(filename, &self.filter, original_line)?))
+      }
+      VarSource::EnclosingScopes => {
+        let (filename, original_line) = file_and_line(values)?;
+        let separator = self.separator.as_deref().unwrap_or(DEFAULT_SEPARATOR);
+        Ok(VarValue::from(scanner::file_enclosing(filename, &self.filter, original_line, separator)?))
+      }
+      VarSource::Command => {
+        let command = template::replace_all(self.command.as_deref().unwrap_or_default(), values)?;
+        if !command.unresolved.is_empty() {
+          return Err(UserError::UnresolvedPlaceholders {
+            names: command.unresolved,
+            comment: None,
+          });
+        }
+        // variables that run the same command share its output
+        if !outputs.contains_key(&command.text) {
+          let output = subshell::capture_output(&command.text, self.timeout.unwrap_or(DEFAULT_COMMAND_TIMEOUT))?;
+          outputs.insert(command.text.clone(), output);
+        }
+        let output = &outputs[&command.text];
+        if self.filter.as_str().is_empty() {
+          return Ok(VarValue {
+            value: output.trim().to_owned(),
+            line: None,
+            groups: vec![],
+          });
+        }
+        let found = scanner::string_first(output, &self.filter)?;
+        Ok(found.map_or_else(
+          || VarValue::from(scanner::Capture::default()),
+          |found| VarValue {
+            line: None,
+            ..VarValue::from(found)
+          },
+        ))
+      }
+      VarSource::ProjectRoot => {
+        let Some(file) = values.get("file_rel_to_config").or_else(|| values.get("file")) else {
           return Err(UserError::FileNameNotAvailable);
         };
-        let Some(original_line) = values.get("line") else {
-          return Err(UserError::LineNotAvailable);
+        let markers: Vec<String> = if self.markers.is_empty() {
+          DEFAULT_MARKERS.iter().map(|marker| (*marker).to_owned()).collect()
+        } else {
+          self.markers.clone()
+        };
+        let Some(dir) = project_root(file, &markers) else {
+          return Err(UserError::ProjectRootNotFound { file: file.to_owned(), markers });
         };
-        let original_line = original_line.parse().map_err(|_| UserError::LineIsNotANumber { line: original_line.to_owned() })?;
-        scanner::file_upwards(filename, &self.filter, original_line)
+        if self.filter.as_str().is_empty() {
+          return Ok(VarValue {
+            value: dir,
+            line: None,
+            groups: vec![],
+          });
+        }
+        Ok(VarValue::from(filter(&dir, &self.filter)?))
       }
+      VarSource::RangeContent => range_content(values, &self.filter),
+      VarSource::FirstMatchInFile => {
+        let Some(filename) = values.get("file") else {
+          return Err(UserError::FileNameNotAvailable);
+        };
+        Ok(VarValue::from(scanner::file_first(filename, &self.filter)?))
+      }
+    }
+  }
+}
+
+impl From<scanner::Match> for VarValue {
+  fn from(found: scanner::Match) -> Self {
+    VarValue {
+      value: found.text,
+      line: Some(found.line),
+      groups: found.groups.into_iter().map(|(_, value)| value).collect(),
+    }
+  }
+}
+
+impl From<scanner::Capture> for VarValue {
+  fn from(capture: scanner::Capture) -> Self {
+    VarValue {
+      value: capture.text,
+      line: None,
+      groups: capture.groups.into_iter().map(|(_, value)| value).collect(),
     }
   }
 }
 
+/// provides the distinct captures of the given regex in the line range of the given placeholder values as a list
+fn range_content(values: &AHashMap<&str, String>, re: &Regex) -> Result<VarValue> {
+  let (filename, line) = file_and_line(values)?;
+  let end_line = match values.get("end_line") {
+    Some(end_line) => end_line.parse().map_err(|_| UserError::LineIsNotANumber { line: end_line.to_owned() })?,
+    None => line,
+  };
+  let found = scanner::file_range(filename, re, line, end_line)?;
+  let mut names: Vec<&str> = Vec::with_capacity(found.len());
+  for found in &found {
+    if !names.contains(&found.text.as_str()) {
+      names.push(&found.text);
+    }
+  }
+  Ok(VarValue {
+    value: names.join(template::LIST_SEPARATOR),
+    line: found.first().map(|found| found.line),
+    groups: vec![],
+  })
+}
+
+/// provides the file and line that the given placeholder values contain
+fn file_and_line<'a>(values: &'a AHashMap<&str, String>) -> Result<(&'a str, usize)> {
+  let Some(filename) = values.get("file") else {
+    return Err(UserError::FileNameNotAvailable);
+  };
+  let Some(line) = values.get("line") else {
+    return Err(UserError::LineNotAvailable);
+  };
+  let line = line.parse().map_err(|_| UserError::LineIsNotANumber { line: line.to_owned() })?;
+  Ok((filename, line))
+}
+
 impl PartialEq for Var {
   fn eq(&self, other: &Self) -> bool {
-    self.name == other.name && self.source == other.source && self.filter.to_string() == other.filter.to_string()
+    self.name == other.name
+      && self.source == other.source
+      && self.filter.as_str() == other.filter.as_str()
+      && self.separator == other.separator
+      && self.group_placeholders == other.group_placeholders
+      && self.command == other.command
+      && self.timeout == other.timeout
+      && self.markers == other.markers
   }
 }
 
@@ -50,33 +221,50 @@ impl TryFrom<FileVar> for Var {
   type Error = UserError;
 
   fn try_from(value: FileVar) -> std::result::Result<Self, Self::Error> {
+    if value.source == VarSource::Command && value.command.is_none() {
+      return Err(UserError::VarCommandMissing { name: value.name });
+    }
+    if !matches!(value.source, VarSource::Command | VarSource::ProjectRoot) && value.filter.is_empty() {
+      return Err(UserError::VarFilterIsEmpty { name: value.name });
+    }
     let filter = regex::Regex::new(&value.filter).map_err(|err| UserError::InvalidRegex {
       regex: value.filter,
       err: err.to_string(),
     })?;
+    if let Some(command) = &value.command {
+      template::parse(command)?;
+    }
+    let timeout = value.timeout.map(parse_timeout).transpose()?;
+    let group_placeholders = filter.capture_names().flatten().map(|group| format!("{}.{group}", value.name)).collect();
     Ok(Var {
       name: value.name,
       source: value.source,
       filter,
+      separator: value.separator,
+      group_placeholders,
+      command: value.command,
+      timeout,
+      markers: value.markers.unwrap_or_default(),
     })
   }
 }
 
-fn filter(text: &str, filter: &Regex) -> Result<String> {
-  let Some(captures) = filter.captures(text) else {
-    return Ok(String::new());
-  };
-  let Some(result) = captures.get(1) else {
-    return Ok(String::new());
-  };
-  if captures.len() > 2 {
-    return Err(UserError::TriggerTooManyCaptures {
-      count: captures.len(),
-      regex: filter.to_string(),
-      line: text.to_owned(),
-    });
+/// provides the closest directory that contains the given file and one of the given marker files,
+/// relative to the current directory if the file is in it
+fn project_root(file: &str, markers: &[String]) -> Option<String> {
+  let mut dir = Path::new(file).parent();
+  while let Some(candidate) = dir {
+    let candidate_path = if candidate.as_os_str().is_empty() { Path::new(".") } else { candidate };
+    if markers.iter().any(|marker| candidate_path.join(marker).is_file()) {
+      return Some(candidate_path.to_string_lossy().to_string());
+    }
+    dir = candidate.parent();
   }
-  Ok(result.as_str().to_owned())
+  None
+}
+
+fn filter(text: &str, filter: &Regex) -> Result<scanner::Capture> {
+  Ok(scanner::capture(text, filter)?.unwrap_or_default())
 }
 
 #[cfg(test)]
@@ -93,11 +281,21 @@ mod tests {
         name: S("name"),
         source: VarSource::File,
         filter: regex::Regex::new(regex).unwrap(),
+        separator: None,
+        group_placeholders: vec![],
+        command: None,
+        timeout: None,
+        markers: vec![],
       };
       let right = Var {
         name: S("name"),
         source: VarSource::File,
         filter: regex::Regex::new(regex).unwrap(),
+        separator: None,
+        group_placeholders: vec![],
+        command: None,
+        timeout: None,
+        markers: vec![],
       };
       assert_eq!(left, right);
     }
@@ -108,13 +306,257 @@ mod tests {
         name: S("name"),
         source: VarSource::File,
         filter: regex::Regex::new("left regex").unwrap(),
+        separator: None,
+        group_placeholders: vec![],
+        command: None,
+        timeout: None,
+        markers: vec![],
       };
       let right = Var {
         name: S("name"),
         source: VarSource::File,
         filter: regex::Regex::new("right regex").unwrap(),
+        separator: None,
+        group_placeholders: vec![],
+        command: None,
+        timeout: None,
+        markers: vec![],
+      };
+      assert_ne!(left, right);
+    }
+
+    #[test]
+    fn different_commands() {
+      let left = Var {
+        name: S("name"),
+        source: VarSource::Command,
+        filter: regex::Regex::new("").unwrap(),
+        separator: None,
+        group_placeholders: vec![],
+        command: Some(S("git branch --show-current")),
+        timeout: None,
+        markers: vec![],
+      };
+      let right = Var {
+        name: S("name"),
+        source: VarSource::Command,
+        filter: regex::Regex::new("").unwrap(),
+        separator: None,
+        group_placeholders: vec![],
+        command: Some(S("git rev-parse HEAD")),
+        timeout: None,
+        markers: vec![],
       };
       assert_ne!(left, right);
     }
   }
+
+  mod insert_into {
+    use super::super::{CommandOutputs, Var};
+    use crate::UserError;
+    use crate::config::VarSource;
+    use crate::config::file::FileVar;
+    use ahash::AHashMap;
+    use big_s::S;
+
+    #[test]
+    fn named_groups() {
+      let var = Var::try_from(FileVar {
+        name: S("test"),
+        source: VarSource::File,
+        filter: S(r"^(?<dir>\w+)/(?<module>\w+)\.rs$"),
+        separator: None,
+        command: None,
+        timeout: None,
+        markers: None,
+      })
+      .unwrap();
+      assert_eq!(var.group_placeholders, vec![S("test.dir"), S("test.module")]);
+      let mut values = AHashMap::from([("file", S("src/parser.rs"))]);
+      var.insert_into(&mut values, &mut CommandOutputs::new()).unwrap();
+      assert_eq!(values["test"], "src");
+      assert_eq!(values["test.dir"], "src");
+      assert_eq!(values["test.module"], "parser");
+    }
+
+    #[test]
+    fn unmatched_named_group() {
+      let var = Var::try_from(FileVar {
+        name: S("test"),
+        source: VarSource::File,
+        filter: S(r"^(?<dir>\w+/)?(?<module>\w+)\.rs$"),
+        separator: None,
+        command: None,
+        timeout: None,
+        markers: None,
+      })
+      .unwrap();
+      let mut values = AHashMap::from([("file", S("parser.rs"))]);
+      var.insert_into(&mut values, &mut CommandOutputs::new()).unwrap();
+      assert_eq!(values["test.dir"], "");
+      assert_eq!(values["test.module"], "parser");
+    }
+
+    #[test]
+    fn multiple_unnamed_groups() {
+      let var = Var::try_from(FileVar {
+        name: S("test"),
+        source: VarSource::File,
+        filter: S(r"^(\w+)/(\w+)\.rs$"),
+        separator: None,
+        command: None,
+        timeout: None,
+        markers: None,
+      })
+      .unwrap();
+      let mut values = AHashMap::from([("file", S("src/parser.rs"))]);
+      let have = var.insert_into(&mut values, &mut CommandOutputs::new());
+      assert!(matches!(have, Err(UserError::TriggerTooManyCaptures { .. })));
+    }
+  }
+
+  mod command {
+    use super::super::{CommandOutputs, Var};
+    use crate::UserError;
+    use crate::config::VarSource;
+    use crate::config::file::FileVar;
+    use ahash::AHashMap;
+    use big_s::S;
+
+    fn command_var(command: &str, filter: &str, timeout: Option<f64>) -> Var {
+      Var::try_from(FileVar {
+        name: S("value"),
+        source: VarSource::Command,
+        filter: filter.to_owned(),
+        separator: None,
+        command: Some(command.to_owned()),
+        timeout,
+        markers: None,
+      })
+      .unwrap()
+    }
+
+    #[test]
+    fn filtered_output() {
+      let var = command_var("echo package {{file_stem}}", "^package (\\w+)", None);
+      let values = AHashMap::from([("file_stem", S("parser"))]);
+      let mut outputs = CommandOutputs::new();
+      let have = var.calculate(&values, &mut outputs).unwrap();
+      assert_eq!(have.value, "parser");
+      assert_eq!(outputs["echo package parser"], "package parser\n");
+    }
+
+    #[test]
+    fn unfiltered_output() {
+      let var = command_var("echo  main ", "", None);
+      let have = var.calculate(&AHashMap::new(), &mut CommandOutputs::new()).unwrap();
+      assert_eq!(have.value, "main");
+    }
+
+    #[test]
+    fn cached_output() {
+      let var = command_var("false", "", None);
+      let mut outputs = CommandOutputs::from([(S("false"), S("cached"))]);
+      let have = var.calculate(&AHashMap::new(), &mut outputs).unwrap();
+      assert_eq!(have.value, "cached");
+    }
+
+    #[test]
+    fn failing_command() {
+      let var = command_var("false", "", None);
+      let have = var.calculate(&AHashMap::new(), &mut CommandOutputs::new());
+      assert!(matches!(have, Err(UserError::VarCommandFailed { .. })));
+    }
+
+    #[test]
+    fn timeout() {
+      let var = command_var("sleep 5", "", Some(0.1));
+      let have = var.calculate(&AHashMap::new(), &mut CommandOutputs::new());
+      assert!(matches!(have, Err(UserError::VarCommandTimeout { .. })));
+    }
+
+    #[test]
+    fn missing_command() {
+      let have = Var::try_from(FileVar {
+        name: S("value"),
+        source: VarSource::Command,
+        filter: S(""),
+        separator: None,
+        command: None,
+        timeout: None,
+        markers: None,
+      });
+      assert_eq!(have.unwrap_err(), UserError::VarCommandMissing { name: S("value") });
+    }
+
+    #[test]
+    fn invalid_command_template() {
+      let have = Var::try_from(FileVar {
+        name: S("value"),
+        source: VarSource::Command,
+        filter: S(""),
+        separator: None,
+        command: Some(S("go list {{ file_dir | zonk }}")),
+        timeout: None,
+        markers: None,
+      });
+      let want = UserError::TemplateSyntax {
+        template: S("go list {{ file_dir | zonk }}"),
+        column: 23,
+        problem: S("unknown filter: zonk"),
+      };
+      assert_eq!(have.unwrap_err(), want);
+    }
+
+    #[test]
+    fn missing_filter() {
+      let have = Var::try_from(FileVar {
+        name: S("value"),
+        source: VarSource::File,
+        filter: S(""),
+        separator: None,
+        command: None,
+        timeout: None,
+        markers: None,
+      });
+      assert_eq!(have.unwrap_err(), UserError::VarFilterIsEmpty { name: S("value") });
+    }
+  }
+
+  mod project_root {
+    use super::super::project_root;
+    use big_s::S;
+    use std::fs;
+
+    #[test]
+    fn nearest_marker() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      fs::create_dir_all(temp_dir.path().join("crates/parser/src")).unwrap();
+      fs::write(temp_dir.path().join("Cargo.toml"), "").unwrap();
+      fs::write(temp_dir.path().join("crates/parser/Cargo.toml"), "").unwrap();
+      let file = temp_dir.path().join("crates/parser/src/lib.rs");
+      let have = project_root(&file.to_string_lossy(), &[S("Cargo.toml")]);
+      let want = Some(temp_dir.path().join("crates/parser").to_string_lossy().to_string());
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn any_of_the_markers() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      fs::create_dir_all(temp_dir.path().join("web/src")).unwrap();
+      fs::write(temp_dir.path().join("web/package.json"), "").unwrap();
+      let file = temp_dir.path().join("web/src/app.ts");
+      let have = project_root(&file.to_string_lossy(), &[S("Cargo.toml"), S("package.json")]);
+      let want = Some(temp_dir.path().join("web").to_string_lossy().to_string());
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn no_marker() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      let file = temp_dir.path().join("app.ts");
+      let have = project_root(&file.to_string_lossy(), &[S("contest-test-marker-that-does-not-exist")]);
+      assert_eq!(have, None);
+    }
+  }
 }
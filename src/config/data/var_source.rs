@@ -2,12 +2,19 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use std::fmt::{self, Display};
 
-#[derive(Debug, Deserialize, Eq, JsonSchema, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum VarSource {
   File,
   Line,
   CurrentOrAboveLineContent,
+  CurrentOrBelowLineContent,
+  NearestLineContent,
+  FirstMatchInFile,
+  EnclosingScopes,
+  Command,
+  ProjectRoot,
+  RangeContent,
 }
 
 impl Display for VarSource {
@@ -16,6 +23,13 @@ impl Display for VarSource {
       VarSource::File => "file",
       VarSource::Line => "line",
       VarSource::CurrentOrAboveLineContent => "currentOrAboveLineContent",
+      VarSource::CurrentOrBelowLineContent => "currentOrBelowLineContent",
+      VarSource::NearestLineContent => "nearestLineContent",
+      VarSource::FirstMatchInFile => "firstMatchInFile",
+      VarSource::EnclosingScopes => "enclosingScopes",
+      VarSource::Command => "command",
+      VarSource::ProjectRoot => "projectRoot",
+      VarSource::RangeContent => "rangeContent",
     };
     write!(f, "{text}")
   }
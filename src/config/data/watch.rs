@@ -0,0 +1,93 @@
+use crate::UserError;
+use crate::config::file::FileWatch;
+use std::time::Duration;
+
+/// configuration for detecting file changes in "contest watch" mode
+#[derive(Debug, Eq, PartialEq)]
+pub struct Watch {
+  pub debounce: Duration,
+  pub ignore: Vec<glob::Pattern>,
+  pub gitignore: bool,
+  pub fallback_to_test_all: bool,
+}
+
+impl Default for Watch {
+  fn default() -> Self {
+    Self {
+      debounce: Duration::from_millis(100),
+      ignore: vec![],
+      gitignore: true,
+      fallback_to_test_all: false,
+    }
+  }
+}
+
+impl TryFrom<FileWatch> for Watch {
+  type Error = UserError;
+
+  fn try_from(value: FileWatch) -> Result<Self, Self::Error> {
+    let defaults = Watch::default();
+    let file_ignore = value.ignore.unwrap_or_default();
+    let mut ignore = Vec::with_capacity(file_ignore.len());
+    for pattern in file_ignore {
+      ignore.push(glob::Pattern::new(&pattern).map_err(|err| UserError::ConfigInvalidGlob { pattern, err: err.to_string() })?);
+    }
+    Ok(Watch {
+      debounce: value.debounce.map_or(defaults.debounce, |millis| Duration::from_millis(millis as u64)),
+      ignore,
+      gitignore: value.gitignore.unwrap_or(defaults.gitignore),
+      fallback_to_test_all: value.fallback_to_test_all.unwrap_or(defaults.fallback_to_test_all),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod into_domain {
+    use super::super::FileWatch;
+    use crate::config::Watch;
+    use big_s::S;
+    use std::time::Duration;
+
+    #[test]
+    fn empty() {
+      let have = Watch::try_from(FileWatch::default()).unwrap();
+      let want = Watch {
+        debounce: Duration::from_millis(100),
+        ignore: vec![],
+        gitignore: true,
+        fallback_to_test_all: false,
+      };
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn with_content() {
+      let file_watch = FileWatch {
+        debounce: Some(500),
+        ignore: Some(vec![S("dist/**")]),
+        gitignore: Some(false),
+        fallback_to_test_all: Some(true),
+      };
+      let have = Watch::try_from(file_watch).unwrap();
+      let want = Watch {
+        debounce: Duration::from_millis(500),
+        ignore: vec![glob::Pattern::new("dist/**").unwrap()],
+        gitignore: false,
+        fallback_to_test_all: true,
+      };
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn invalid_ignore_glob() {
+      let file_watch = FileWatch {
+        ignore: Some(vec![S("[")]),
+        ..FileWatch::default()
+      };
+      let have = Watch::try_from(file_watch);
+      assert!(have.is_err());
+    }
+  }
+}
@@ -1,9 +1,10 @@
 //! data structures as they are in the file
 
-use crate::config::VarSource;
+use crate::config::{ParallelOutput, VarSource};
 use schemars::JsonSchema;
 use schemars::schema::{InstanceType, NumberValidation, SchemaObject};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fmt::Display;
 
 /// Schema helper for Option<usize> that generates standard JSON Schema integer without "format": "uint"
@@ -30,24 +31,74 @@ pub struct FileConfiguration {
 
 /// a particular test
 #[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct FileAction {
   pub r#type: ActionType,
   /// the files for which this command applies as a glob expression
   pub files: Option<String>,
+  /// for "test-dir" actions: the directories for which this command applies as a glob expression
+  pub dirs: Option<String>,
   /// the command to run
-  pub run: String,
+  ///
+  /// Actions for triggers with a file can use these placeholders:
+  /// `{{file}}` (path as sent by the client), `{{file_name}}` (name with extension),
+  /// `{{file_stem}}` (name without extension), `{{file_ext}}` (extension without dot),
+  /// `{{file_dir}}` (directory), `{{file_abs}}` (absolute path),
+  /// `{{file_rel_to_config}}` (path relative to the directory containing contest.json),
+  /// `{{file_module_dots}}` and `{{file_module_colons}}` (path relative to contest.json without extension, separated by "." or "::").
+  /// "test-file-line" actions can also use `{{line}}`, "test-file-range" actions `{{line}}` and `{{end_line}}`.
+  /// A list of commands runs them as steps one after the other.
+  pub run: FileRun,
   /// define additional variables to use in the "run" string
   pub vars: Option<Vec<FileVar>>,
   /// human-readable description of this action
   pub comment: Option<String>,
+  /// how many seconds the command may run before Contest stops it
+  pub timeout: Option<f64>,
+  /// the shell that executes the command, for example "sh -c"
+  pub shell: Option<String>,
+  /// the directory in which to run the command, can contain placeholders
+  pub cwd: Option<String>,
+  /// additional environment variables for the command, the values can contain placeholders
+  pub env: Option<BTreeMap<String, String>>,
+  /// for "test-file-line" actions: whether to run the matching "test-file" action, or else the "test-all" action, if a variable finds no match
+  pub fallback: Option<bool>,
+  /// for actions with several steps: whether to run the remaining steps after a step fails
+  pub continue_on_error: Option<bool>,
+  /// for actions with several steps: whether to run all steps at the same time
+  pub parallel: Option<bool>,
+  /// for parallel steps: "grouped" prints the output of each step under a header once all steps are done,
+  /// "prefixed" prints the output as it arrives with the step number in front of each line
+  pub parallel_output: Option<ParallelOutput>,
+}
+
+/// the command of an action as it is in the file
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq)]
+#[serde(untagged)]
+pub enum FileRun {
+  /// a single command
+  Single(String),
+  /// several commands that run one after the other
+  Steps(Vec<String>),
+}
+
+impl From<FileRun> for Vec<String> {
+  fn from(value: FileRun) -> Self {
+    match value {
+      FileRun::Single(run) => vec![run],
+      FileRun::Steps(steps) => steps,
+    }
+  }
 }
 
-#[derive(Debug, Deserialize, Eq, JsonSchema, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, JsonSchema, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum ActionType {
   TestAll,
   TestFile,
   TestFileLine,
+  TestFileRange,
+  TestDir,
 }
 
 impl Display for ActionType {
@@ -56,19 +107,30 @@ impl Display for ActionType {
       ActionType::TestAll => "test-all",
       ActionType::TestFile => "test-file",
       ActionType::TestFileLine => "test-file-line",
+      ActionType::TestFileRange => "test-file-range",
+      ActionType::TestDir => "test-dir",
     })
   }
 }
 
 /// an additional variable that gets derived from the file content
-#[derive(Deserialize, Debug, Eq, JsonSchema, PartialEq)]
+#[derive(Clone, Deserialize, Debug, JsonSchema, PartialEq)]
 pub struct FileVar {
   /// name of the variable, available as "{{ name }}" later
   pub name: String,
   /// the location in the file
   pub source: VarSource,
-  /// how the variable gets computed
+  /// a regex whose first capture group provides the value, named capture groups become additional placeholders like "{{ name.group }}"
+  #[serde(default)]
   pub filter: String,
+  /// for the "enclosingScopes" source: the text between the names of nested scopes, defaults to "::"
+  pub separator: Option<String>,
+  /// for the "command" source: the command whose output provides the value, can contain placeholders
+  pub command: Option<String>,
+  /// for the "command" source: how many seconds the command may run, defaults to 10
+  pub timeout: Option<f64>,
+  /// for the "projectRoot" source: names of files that mark the root directory of a project
+  pub markers: Option<Vec<String>>,
 }
 
 #[derive(Default, Deserialize, JsonSchema)]
@@ -78,6 +140,21 @@ pub struct FileOptions {
   pub before_run: Option<FileBeforeRun>,
   /// configure behavior after Contest runs a test
   pub after_run: Option<FileAfterRun>,
+  /// whether a new trigger cancels the currently running test
+  pub cancel_on_new_trigger: Option<bool>,
+  /// how many seconds test commands may run before Contest stops them
+  pub timeout: Option<f64>,
+  /// configure how "contest watch" detects file changes
+  pub watch: Option<FileWatch>,
+  /// how many past test runs to keep in the history, defaults to 0 which disables the history
+  #[schemars(schema_with = "option_usize_schema", default)]
+  pub history: Option<usize>,
+  /// the shell that executes the commands of all actions, for example "sh -c"
+  pub shell: Option<String>,
+  /// additional environment variables for the commands of all actions, the values can contain placeholders
+  pub env: Option<BTreeMap<String, String>>,
+  /// whether commands with placeholders that have no value fail instead of running, defaults to true
+  pub strict_placeholders: Option<bool>,
 }
 
 #[derive(Default, Deserialize, JsonSchema)]
@@ -90,6 +167,20 @@ pub struct FileBeforeRun {
   pub newlines: Option<usize>,
 }
 
+#[derive(Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileWatch {
+  /// how many milliseconds to wait for further file changes before running tests
+  #[schemars(schema_with = "option_usize_schema", default)]
+  pub debounce: Option<usize>,
+  /// glob expressions for files whose changes don't trigger tests
+  pub ignore: Option<Vec<String>>,
+  /// whether to ignore changes to files listed in the .gitignore files of the workspace, defaults to true
+  pub gitignore: Option<bool>,
+  /// whether to test all files if no "test-file" action matches the changed file
+  pub fallback_to_test_all: Option<bool>,
+}
+
 #[derive(Default, Deserialize, Eq, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FileAfterRun {
@@ -2,5 +2,7 @@
 
 mod data;
 pub mod file;
+mod validation;
 
-pub use data::{Action, AfterRun, BeforeRun, Configuration, JSON_PATH, Options, Pattern, Var, VarSource};
+pub use data::{Action, AfterRun, BeforeRun, Command, Configuration, JSON_PATH, Options, ParallelOutput, Pattern, Shell, Var, VarSource, VarValue, Watch};
+pub use validation::{Location, Problem, validate, validate_file};
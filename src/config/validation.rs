@@ -0,0 +1,341 @@
+//! finds all problems in a configuration file
+
+use super::file::{FileAction, FileConfiguration};
+use super::{Action, Options, Pattern, Var};
+use crate::template::{self, Segment};
+use crate::{Result, UserError};
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::Path;
+
+/// a problem in the configuration file
+#[derive(Debug, Eq, PartialEq)]
+pub enum Problem {
+  /// the given part of the configuration file contains an invalid value
+  Invalid { location: Location, err: UserError },
+  /// the given part of the configuration file contains a questionable value that Contest can still use
+  Questionable { location: Location, err: UserError },
+  /// the action at the given location never runs because an earlier action always matches first
+  Shadowed { location: Location, shadowed_by: Location },
+}
+
+impl Problem {
+  /// indicates whether this problem prevents Contest from using the configuration file
+  #[must_use]
+  pub fn is_error(&self) -> bool {
+    matches!(self, Problem::Invalid { .. })
+  }
+}
+
+impl Display for Problem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Problem::Invalid { location, err } | Problem::Questionable { location, err } => {
+        let (msg, guidance) = err.clone().messages();
+        let kind = if self.is_error() { "error" } else { "warning" };
+        write!(f, "{kind} in {location}: {msg}")?;
+        if let Some(guidance) = guidance {
+          for line in guidance.lines() {
+            write!(f, "\n  {line}")?;
+          }
+        }
+        Ok(())
+      }
+      Problem::Shadowed { location, shadowed_by } => write!(f, "warning: {location} never runs because {shadowed_by} always matches first"),
+    }
+  }
+}
+
+/// the place in the configuration file where a problem occurs
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Location {
+  Action { index: usize, comment: Option<String> },
+  Options,
+}
+
+impl Display for Location {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Location::Action { index, comment: None } => write!(f, "actions[{index}]"),
+      Location::Action { index, comment: Some(comment) } => write!(f, "actions[{index}] ({comment})"),
+      Location::Options => f.write_str("options"),
+    }
+  }
+}
+
+/// provides all problems in the configuration file at the given path
+pub fn validate_file(path: &Path) -> Result<Vec<Problem>> {
+  let file_content = fs::read_to_string(path).map_err(|err| UserError::ConfigFileError { err: err.to_string() })?;
+  let file_data: FileConfiguration = json5::from_str(&file_content).map_err(|err| UserError::ConfigFileInvalidContent { err: err.to_string() })?;
+  Ok(validate(file_data))
+}
+
+/// provides all problems in the given configuration data
+#[must_use]
+pub fn validate(file_data: FileConfiguration) -> Vec<Problem> {
+  let mut problems = vec![];
+  let strict_placeholders = file_data
+    .options
+    .as_ref()
+    .and_then(|options| options.strict_placeholders)
+    .unwrap_or(Options::default().strict_placeholders);
+  // the valid actions and where they are defined
+  let mut actions: Vec<(Location, Action)> = vec![];
+  for (index, file_action) in file_data.actions.into_iter().enumerate() {
+    let location = || Location::Action {
+      index,
+      comment: file_action.comment.clone(),
+    };
+    let mut valid = true;
+    // the placeholders that the variables of this action define
+    let mut var_names: Vec<String> = vec![];
+    for file_var in file_action.vars.iter().flatten() {
+      var_names.push(file_var.name.clone());
+      match Var::try_from(file_var.clone()) {
+        Ok(var) => var_names.extend(var.group_placeholders),
+        Err(err) => {
+          problems.push(Problem::Invalid { location: location(), err });
+          valid = false;
+        }
+      }
+    }
+    let location = location();
+    // the variables have been checked above
+    let file_action = FileAction { vars: None, ..file_action };
+    match Action::try_from(file_action) {
+      Ok(action) => {
+        let unknown = unknown_placeholders(&action, &var_names);
+        if !unknown.is_empty() {
+          let location = location.clone();
+          let err = UserError::UnknownPlaceholders { names: unknown };
+          // without strict placeholders, Contest runs such commands with the placeholders left as they are
+          problems.push(if strict_placeholders {
+            Problem::Invalid { location, err }
+          } else {
+            Problem::Questionable { location, err }
+          });
+        }
+        if valid {
+          actions.push((location, action));
+        }
+      }
+      Err(err) => problems.push(Problem::Invalid { location, err }),
+    }
+  }
+  if let Err(err) = Options::try_from(file_data.options.unwrap_or_default()) {
+    problems.push(Problem::Invalid { location: Location::Options, err });
+  }
+  for (later_index, (later_location, later_action)) in actions.iter().enumerate() {
+    if let Some((earlier_location, _)) = actions[..later_index]
+      .iter()
+      .find(|(_, earlier_action)| always_matches_first(&earlier_action.pattern, &later_action.pattern))
+    {
+      problems.push(Problem::Shadowed {
+        location: later_location.clone(),
+        shadowed_by: earlier_location.clone(),
+      });
+    }
+  }
+  problems
+}
+
+/// provides the names of the placeholders in the "run" field of the given action
+/// that are neither built into its type nor defined as one of the given variables
+fn unknown_placeholders(action: &Action, var_names: &[String]) -> Vec<String> {
+  let mut known: Vec<&str> = action.pattern.builtin_placeholders();
+  known.extend(var_names.iter().map(String::as_str));
+  let mut result: Vec<String> = vec![];
+  // syntax errors in the template have been reported when creating the action
+  for step in &action.run {
+    collect_unknown_placeholders(&template::parse(step).unwrap_or_default(), &known, &mut result);
+  }
+  result
+}
+
+fn collect_unknown_placeholders(segments: &[Segment], known: &[&str], result: &mut Vec<String>) {
+  for segment in segments {
+    let placeholder = match segment {
+      Segment::Text(_) => continue,
+      Segment::Placeholder(placeholder) => placeholder,
+      Segment::Block(block) => {
+        collect_unknown_placeholders(&block.body, known, result);
+        &block.condition
+      }
+    };
+    if !placeholder.has_default() && !known.contains(&placeholder.name.as_str()) && !result.contains(&placeholder.name) {
+      result.push(placeholder.name.clone());
+    }
+  }
+}
+
+/// Indicates whether the given earlier pattern matches all triggers that the given later pattern matches.
+/// This is the case if the earlier glob matches the later glob expression as if it were a filename.
+fn always_matches_first(earlier: &Pattern, later: &Pattern) -> bool {
+  match (earlier, later) {
+    (Pattern::TestAll, Pattern::TestAll) => true,
+    (Pattern::TestFile { files: earlier }, Pattern::TestFile { files: later })
+    | (Pattern::TestFileLine { files: earlier }, Pattern::TestFileLine { files: later })
+    | (Pattern::TestFileRange { files: earlier }, Pattern::TestFileRange { files: later })
+    | (Pattern::TestDir { dirs: earlier }, Pattern::TestDir { dirs: later }) => earlier.matches(later.as_str()),
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod validate {
+    use super::super::{Location, Problem, validate};
+    use crate::UserError;
+    use crate::config::file::FileConfiguration;
+    use big_s::S;
+
+    fn parse(json: &str) -> FileConfiguration {
+      json5::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn valid() {
+      let file_data = parse(
+        r#"{
+          "actions": [
+            { "type": "test-all", "run": "make test" },
+            { "type": "test-file", "files": "**/*.rs", "run": "cargo test {{file}}" }
+          ]
+        }"#,
+      );
+      let have = validate(file_data);
+      assert_eq!(have, vec![]);
+    }
+
+    #[test]
+    fn reports_all_problems() {
+      let file_data = parse(
+        r#"{
+          "actions": [
+            { "type": "test-file", "files": "[", "run": "echo {{foo}}", "comment": "broken", "vars": [
+              { "name": "foo", "source": "file", "filter": "(" }
+            ] },
+            { "type": "test-all", "run": "" }
+          ]
+        }"#,
+      );
+      let problems = validate(file_data);
+      assert!(problems.iter().all(Problem::is_error));
+      let have: Vec<Location> = problems
+        .into_iter()
+        .map(|problem| match problem {
+          Problem::Invalid { location, err: _ } | Problem::Questionable { location, err: _ } | Problem::Shadowed { location, shadowed_by: _ } => location,
+        })
+        .collect();
+      let want = vec![
+        Location::Action {
+          index: 0,
+          comment: Some(S("broken")),
+        },
+        Location::Action {
+          index: 0,
+          comment: Some(S("broken")),
+        },
+        Location::Action { index: 1, comment: None },
+      ];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn unknown_placeholders() {
+      let file_data = parse(
+        r#"{
+          "actions": [
+            { "type": "test-all", "run": "make {{file}} {{target}} {{mode | default:\"debug\"}}{{#if verbose}}-v{{/if}}" },
+            { "type": "test-file-line", "files": "**/*.rs", "run": "cargo test {{file}}:{{line}} {{name}} {{name.fn}}", "vars": [
+              { "name": "name", "source": "currentOrAboveLineContent", "filter": "fn (?<fn>\\w+)" }
+            ] }
+          ]
+        }"#,
+      );
+      let have = validate(file_data);
+      let want = vec![Problem::Invalid {
+        location: Location::Action { index: 0, comment: None },
+        err: UserError::UnknownPlaceholders {
+          names: vec![S("file"), S("target"), S("verbose")],
+        },
+      }];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn unknown_placeholders_without_strict_placeholders() {
+      let file_data = parse(
+        r#"{
+          "actions": [
+            { "type": "test-all", "run": "make {{target}}" }
+          ],
+          "options": { "strictPlaceholders": false }
+        }"#,
+      );
+      let have = validate(file_data);
+      let want = vec![Problem::Questionable {
+        location: Location::Action { index: 0, comment: None },
+        err: UserError::UnknownPlaceholders { names: vec![S("target")] },
+      }];
+      assert_eq!(have, want);
+      assert!(!have[0].is_error());
+    }
+
+    #[test]
+    fn invalid_options() {
+      let file_data = parse(
+        r#"{
+          "actions": [],
+          "options": { "timeout": -1 }
+        }"#,
+      );
+      let have = validate(file_data);
+      assert_eq!(have.len(), 1);
+      assert!(matches!(
+        &have[0],
+        Problem::Invalid {
+          location: Location::Options,
+          err: UserError::InvalidTimeout { .. }
+        }
+      ));
+    }
+
+    #[test]
+    fn shadowed_action() {
+      let file_data = parse(
+        r#"{
+          "actions": [
+            { "type": "test-file", "files": "**/*.rs", "run": "cargo test" },
+            { "type": "test-file-line", "files": "src/*.rs", "run": "cargo test {{line}}" },
+            { "type": "test-file", "files": "src/*.rs", "run": "cargo test {{file}}", "comment": "single file" }
+          ]
+        }"#,
+      );
+      let have = validate(file_data);
+      let want = vec![Problem::Shadowed {
+        location: Location::Action {
+          index: 2,
+          comment: Some(S("single file")),
+        },
+        shadowed_by: Location::Action { index: 0, comment: None },
+      }];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn narrower_action_first() {
+      let file_data = parse(
+        r#"{
+          "actions": [
+            { "type": "test-file", "files": "src/*.rs", "run": "cargo test {{file}}" },
+            { "type": "test-file", "files": "**/*.rs", "run": "cargo test" }
+          ]
+        }"#,
+      );
+      let have = validate(file_data);
+      assert_eq!(have, vec![]);
+    }
+  }
+}
@@ -1,57 +1,104 @@
 //! error types used in this app
 
 use crate::config;
-use crate::config::file::ActionType;
 use big_s::S;
+use std::time::Duration;
 
 /// The possible errors that the user can cause and needs to be notified about.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum UserError {
   CannotCreateConfigFile { err: String },
   CannotDetermineCurrentDirectory { err: String },
+  CannotEnterDirectory { path: String, err: String },
   CannotReadFile { path: String, err: String },
   CannotSplitShellString { source: String, err: String },
+  CannotWaitForCommand { err: String },
   ConfigFileError { err: String },
+  ConfigFileHasErrors { count: usize },
   ConfigFileInvalidContent { err: String },
+  ConfigFileNotFound,
   ConfigInvalidGlob { pattern: String, err: String },
+  FallbackRequiresTestFileLine,
   FifoAlreadyExists { path: String },
   FifoCannotCreate { path: String, err: String },
   FifoCannotDelete { path: String, err: String },
   FifoCannotOpen { err: String },
   FifoCannotRead { err: String },
+  DirsIsEmpty,
   FilesIsEmpty,
   FileNameNotAvailable,
+  FileRegexNotFound { regex: String, filename: String },
+  HistoryCannotWrite { path: String, err: String },
+  HistoryRunNotFound { id: usize },
   InvalidRegex { regex: String, err: String },
+  InvalidTimeout { timeout: String, err: String },
   InvalidTrigger { source: String, err: String },
   LineIsNotANumber { line: String },
   LineNotAvailable,
+  MissingDirsInPattern,
   MissingFilesInPattern,
+  MissingEndLineInTrigger { original: String },
   MissingFileInTrigger { original: String },
   MissingFileAndLineInTrigger { original: String },
   MissingFilesInTestFile { original: String },
   MissingLineInTrigger { original: String },
   MissingRunInTrigger { original: String },
   NoCommandToRepeat,
+  ProjectRootNotFound { file: String, markers: Vec<String> },
   RunCommandNotFound { command: String },
   RunCommandIsEmpty,
+  RunDirectoryNotFound { path: String },
+  ShellIsEmpty,
+  SocketAlreadyExists { path: String },
+  SocketCannotCreate { path: String, err: String },
+  SocketCannotDelete { path: String, err: String },
+  TemplateSyntax { template: String, column: usize, problem: String },
+  UnknownPlaceholders { names: Vec<String> },
+  UnresolvedPlaceholders { names: Vec<String>, comment: Option<String> },
   TriggerTooManyCaptures { count: usize, regex: String, line: String },
   TriggerRegexNotFound { regex: String, filename: String, line: usize },
-  UnknownActionType { action_type: ActionType },
   UnknownTrigger { source: String },
+  VarCommandFailed { command: String, output: String },
+  VarCommandMissing { name: String },
+  VarCommandTimeout { command: String, timeout: Duration },
+  VarFilterIsEmpty { name: String },
+  WatcherCannotStart { err: String },
 }
 
 impl UserError {
+  /// indicates whether this error means that a variable found no match, which the fallback of an action can handle
+  #[must_use]
+  pub fn is_no_match(&self) -> bool {
+    matches!(
+      self,
+      UserError::TriggerRegexNotFound { regex: _, filename: _, line: _ } | UserError::FileRegexNotFound { regex: _, filename: _ }
+    )
+  }
+
   /// Provides human-readable messages for `UserError`.
   #[must_use]
+  #[allow(clippy::too_many_lines)] // one match arm per error
   pub fn messages(self) -> (String, Option<String>) {
     match self {
       UserError::CannotCreateConfigFile { err } => (format!("cannot create configuration file: {err}"), None),
       UserError::CannotDetermineCurrentDirectory { err } => (format!("cannot determine the current directory: {err}"), None),
+      UserError::CannotEnterDirectory { path, err } => (format!("cannot enter directory {path}"), Some(err)),
       UserError::CannotReadFile { path, err } => (format!("cannot read file {path}"), Some(err)),
       UserError::CannotSplitShellString { source, err } => (format!("cannot split this shell string: {source}"), Some(err)),
+      UserError::CannotWaitForCommand { err } => (format!("cannot wait for the test command to finish: {err}"), None),
+      UserError::ConfigFileHasErrors { count: 1 } => (S("the configuration file contains 1 error"), None),
+      UserError::ConfigFileHasErrors { count } => (format!("the configuration file contains {count} errors"), None),
+      UserError::ConfigFileNotFound => (
+        format!("cannot find {}", config::JSON_PATH),
+        Some(S(r#"Please run "contest init" to create a configuration file."#)),
+      ),
       UserError::ConfigFileInvalidContent { err } => (format!("Cannot parse configuration file: {err}"), None),
       UserError::ConfigFileError { err } => (format!("Cannot open configuration file: {err}"), None),
       UserError::ConfigInvalidGlob { pattern, err } => (format!("Invalid glob pattern: {pattern}"), Some(err)),
+      UserError::FallbackRequiresTestFileLine => (
+        S(r#"only "test-file-line" actions can have a "fallback""#),
+        Some(S("Other action types have no broader action to fall back to.")),
+      ),
       UserError::FifoAlreadyExists { path } => (
         format!("A fifo pipe \"{path}\" already exists."),
         Some(S(
@@ -68,8 +115,16 @@ impl UserError {
           r#"To use the filename in a variable, you need to choose either the "test-file" or "test-file-line" action type that provides this data."#,
         )),
       ),
+      UserError::FileRegexNotFound { regex, filename } => (format!("did not find pattern {regex} in file {filename}"), Some(S("This is defined in file contest.json."))),
+      UserError::DirsIsEmpty => (S(r#"The "dirs" field in your config file is empty"#), None),
       UserError::FilesIsEmpty => (S(r#"The "files" field in your config file is empty"#), None),
+      UserError::HistoryCannotWrite { path, err } => (format!("cannot write the test history to {path}"), Some(err)),
+      UserError::HistoryRunNotFound { id } => (
+        format!("test run {id} not found in the history"),
+        Some(S(r#"Run "contest history" to see the available test runs."#)),
+      ),
       UserError::InvalidRegex { regex, err } => (format!("invalid regex: {regex}"), Some(err)),
+      UserError::InvalidTimeout { timeout, err } => (format!("invalid timeout: {timeout}"), Some(err)),
       UserError::InvalidTrigger { source: line, err } => (format!("cannot parse command received from client: {line}"), Some(err)),
       UserError::LineIsNotANumber { line } => (format!("the provided line ({line})is not a number"), None),
       UserError::LineNotAvailable => (
@@ -78,18 +133,52 @@ impl UserError {
           r#"To use the current line in a variable, you need to use the "test-file-line" action type that provides this data."#,
         )),
       ),
+      UserError::MissingDirsInPattern => (S(r#"the pattern in the config file is missing the "dirs" field."#), None),
       UserError::MissingFilesInPattern => (S(r#"the pattern in the config file is missing the "files" field."#), None),
+      UserError::MissingEndLineInTrigger { original } => (format!("invalid trigger received: {original}"), Some(S(r#"missing "end_line" field"#))),
       UserError::MissingFileInTrigger { original } => (format!("invalid trigger received: {original}"), Some(S(r#"missing "file" field"#))),
       UserError::MissingFileAndLineInTrigger { original } => (format!("invalid trigger received: {original}"), Some(S(r#"missing "file" and "line" fields"#))),
       UserError::MissingFilesInTestFile { original } => (format!("invalid trigger received: {original}"), Some(S(r#"missing "files" field"#))),
       UserError::MissingLineInTrigger { original } => (format!("invalid trigger received: {original}"), Some(S(r#"missing "line" field"#))),
       UserError::MissingRunInTrigger { original } => (format!("invalid trigger received: {original}"), Some(S(r#"missing "run" field"#))),
       UserError::NoCommandToRepeat => (S("No command to repeat found"), Some(S("You must submit a test command first before you can repeat it."))),
+      UserError::ProjectRootNotFound { file, markers } => (
+        format!("cannot find the project root of {file}"),
+        Some(format!("None of the directories containing this file contain one of these files: {}", markers.join(", "))),
+      ),
       UserError::RunCommandNotFound { command } => (
         format!("test command to run not found: {command}"),
         Some(S("Please verify that the command is in the path or fix your config file.")),
       ),
+      UserError::RunDirectoryNotFound { path } => (
+        format!("the directory to run the test command in does not exist: {path}"),
+        Some(S(r#"Please verify the "cwd" field in your configuration file."#)),
+      ),
       UserError::RunCommandIsEmpty => (S(r#"the "run" field in your configuration file is empty"#), None),
+      UserError::ShellIsEmpty => (S(r#"the "shell" field in your configuration file is empty"#), None),
+      UserError::SocketAlreadyExists { path } => (
+        format!("A socket \"{path}\" already exists."),
+        Some(S(
+          "This could mean a Contest instance could already be running.\nIf you are sure no other instance is running, please delete this file and start Contest again.",
+        )),
+      ),
+      UserError::SocketCannotCreate { path, err } => (format!("Cannot create socket at {path}: {err}"), None),
+      UserError::SocketCannotDelete { path, err } => (format!("Cannot delete socket at {path}: {err}"), None),
+      UserError::TemplateSyntax { template, column, problem } => (format!("invalid placeholder in \"{template}\" at column {column}: {problem}"), None),
+      UserError::UnknownPlaceholders { names } => (
+        format!("unknown placeholders: {}", placeholder_list(&names)),
+        Some(S("Placeholders must be built into the action type or defined in the \"vars\" section of the action.")),
+      ),
+      UserError::UnresolvedPlaceholders { names, comment } => (
+        format!(
+          "no values for placeholders {}{}",
+          placeholder_list(&names),
+          comment.map(|comment| format!(" in action \"{comment}\"")).unwrap_or_default()
+        ),
+        Some(S(
+          r#"Please define these values in the "vars" section of the action or set "strictPlaceholders" to false in the options."#,
+        )),
+      ),
       UserError::TriggerTooManyCaptures { count, regex, line } => (
         format!("found {count} captures using regex \"{regex}\" on line: {line}"),
         Some(S("filters in the Contest configuration file can only contain one capture group")),
@@ -98,17 +187,32 @@ impl UserError {
         format!("did not find pattern {regex} in file {filename} at line {line}"),
         Some(S("This is defined in file contest.json.")),
       ),
-      UserError::UnknownActionType { action_type } => (
-        format!("unknown action type: {action_type}"),
-        Some(S(r#"Valid types are "test-all", "test-file", and "test-file-line"."#)),
-      ),
       UserError::UnknownTrigger { source } => (
         format!("cannot determine command for trigger: {source}"),
         Some(format!("Please make sure that this action is listed in {}", config::JSON_PATH)),
       ),
+      UserError::VarCommandFailed { command, output } => (format!("the command \"{command}\" of a variable failed"), Some(output)),
+      UserError::VarCommandMissing { name } => (
+        format!("variable \"{name}\" has no command"),
+        Some(S(r#"Variables with the source "command" need a "command" field that contains the command to run."#)),
+      ),
+      UserError::VarCommandTimeout { command, timeout } => (
+        format!("the command \"{command}\" of a variable did not finish within {} seconds", timeout.as_secs_f64()),
+        Some(S(r#"You can increase the "timeout" of the variable."#)),
+      ),
+      UserError::VarFilterIsEmpty { name } => (
+        format!("variable \"{name}\" has no filter"),
+        Some(S(r#"Only variables with the source "command" can omit the "filter" field."#)),
+      ),
+      UserError::WatcherCannotStart { err } => (format!("cannot watch the files in the current directory: {err}"), None),
     }
   }
 }
 
+/// formats the given placeholder names for display in error messages
+fn placeholder_list(names: &[String]) -> String {
+  names.iter().map(|name| format!("{{{{ {name} }}}}")).collect::<Vec<_>>().join(", ")
+}
+
 /// a Result that always has a `UserError` as the error and therefore doesn't require to specify it at each call point
 pub type Result<T> = std::result::Result<T, UserError>;
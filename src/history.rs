@@ -0,0 +1,159 @@
+//! keeps a record of past test runs in the workspace
+
+use crate::{Result, UserError};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::{fs, io};
+
+/// name of the file that stores the history of test runs
+pub const FILE_NAME: &str = ".contest-history.json";
+
+/// the most recent test runs, oldest first
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct History {
+  pub runs: Vec<Run>,
+}
+
+/// a past test run
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Run {
+  /// identifies this run in the history
+  pub id: usize,
+  /// the trigger that caused this run
+  pub trigger: String,
+  /// the command that ran
+  pub command: String,
+  /// when the run started, in seconds since the Unix epoch
+  pub started: u64,
+  /// how long the run took, in milliseconds
+  pub duration: u64,
+  /// the exit code of the command, if it exited on its own
+  pub exit_code: Option<i32>,
+  pub outcome: String,
+  /// everything the command printed
+  pub output: String,
+}
+
+impl History {
+  /// loads the history stored in the file at the given path
+  pub fn load(path: &Path) -> Result<History> {
+    let content = match fs::read_to_string(path) {
+      Ok(content) => content,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(History::default()),
+      Err(err) => {
+        return Err(UserError::CannotReadFile {
+          path: path.to_string_lossy().to_string(),
+          err: err.to_string(),
+        });
+      }
+    };
+    serde_json::from_str(&content).map_err(|err| UserError::CannotReadFile {
+      path: path.to_string_lossy().to_string(),
+      err: err.to_string(),
+    })
+  }
+
+  /// stores this history in the file at the given path
+  pub fn save(&self, path: &Path) -> Result<()> {
+    let content = serde_json::to_string(self).map_err(|err| UserError::HistoryCannotWrite {
+      path: path.to_string_lossy().to_string(),
+      err: err.to_string(),
+    })?;
+    fs::write(path, content).map_err(|err| UserError::HistoryCannotWrite {
+      path: path.to_string_lossy().to_string(),
+      err: err.to_string(),
+    })
+  }
+
+  /// adds the given run under a new id, keeps only the given number of most recent runs
+  pub fn add(&mut self, mut run: Run, keep: usize) {
+    run.id = self.runs.last().map_or(1, |last| last.id + 1);
+    self.runs.push(run);
+    if self.runs.len() > keep {
+      self.runs.drain(..self.runs.len() - keep);
+    }
+  }
+
+  /// provides the run with the given id
+  #[must_use]
+  pub fn get(&self, id: usize) -> Option<&Run> {
+    self.runs.iter().find(|run| run.id == id)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod add {
+    use super::super::{History, Run};
+    use big_s::S;
+
+    fn run(trigger: &str) -> Run {
+      Run {
+        id: 0,
+        trigger: trigger.to_owned(),
+        command: S("make test"),
+        started: 0,
+        duration: 0,
+        exit_code: Some(0),
+        outcome: S("pass"),
+        output: S(""),
+      }
+    }
+
+    #[test]
+    fn assigns_ids() {
+      let mut history = History::default();
+      history.add(run("one"), 10);
+      history.add(run("two"), 10);
+      let have: Vec<(usize, &str)> = history.runs.iter().map(|run| (run.id, run.trigger.as_str())).collect();
+      assert_eq!(have, vec![(1, "one"), (2, "two")]);
+    }
+
+    #[test]
+    fn keeps_the_most_recent_runs() {
+      let mut history = History::default();
+      history.add(run("one"), 2);
+      history.add(run("two"), 2);
+      history.add(run("three"), 2);
+      let have: Vec<(usize, &str)> = history.runs.iter().map(|run| (run.id, run.trigger.as_str())).collect();
+      assert_eq!(have, vec![(2, "two"), (3, "three")]);
+    }
+  }
+
+  mod load {
+    use super::super::{History, Run};
+    use big_s::S;
+
+    #[test]
+    fn missing_file() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      let have = History::load(&temp_dir.path().join("history.json")).unwrap();
+      assert_eq!(have, History::default());
+    }
+
+    #[test]
+    fn roundtrip() {
+      let temp_dir = tempfile::tempdir().unwrap();
+      let path = temp_dir.path().join("history.json");
+      let mut history = History::default();
+      history.add(
+        Run {
+          id: 0,
+          trigger: S("test-all"),
+          command: S("make test"),
+          started: 1_700_000_000,
+          duration: 1234,
+          exit_code: Some(1),
+          outcome: S("fail"),
+          output: S("1 test failed\n"),
+        },
+        10,
+      );
+      history.save(&path).unwrap();
+      let have = History::load(&path).unwrap();
+      assert_eq!(have, history);
+    }
+  }
+}
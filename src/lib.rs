@@ -3,63 +3,251 @@ pub mod cli;
 pub mod client;
 pub mod config;
 mod errors;
+mod history;
 pub(crate) mod scanner;
 mod subshell;
 pub(crate) mod template;
 
-use client::{Fifo, Trigger, fifo};
-use config::Configuration;
+use big_s::S;
+use client::{ConfigWatcher, Fifo, Reply, Responder, Socket, Trigger, Watcher};
+use config::{Command, Configuration};
 pub use errors::{Result, UserError};
+use history::{History, Run};
+use prettytable::Table;
+use prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR;
 use std::env;
 use std::io::Write;
-use subshell::Outcome;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use subshell::{Execution, Outcome};
 use termcolor::WriteColor;
 use terminal_size::{Height, Width, terminal_size};
 
-pub fn listen(config: &Configuration, debug: bool) -> Result<()> {
+pub fn listen(config: Configuration, debug: bool) -> Result<()> {
+  serve(config, if debug { Mode::Debug } else { Mode::Execute })
+}
+
+/// executes the received triggers and tests files when they change
+pub fn watch(config: Configuration) -> Result<()> {
+  serve(config, Mode::Watch)
+}
+
+/// the ways in which Contest can serve clients
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Mode {
+  /// execute the triggers received from clients
+  Execute,
+  /// print the triggers received from clients
+  Debug,
+  /// execute the triggers received from clients and test changed files
+  Watch,
+}
+
+fn serve(config: Configuration, mode: Mode) -> Result<()> {
+  let debug = mode == Mode::Debug;
   if debug {
     println!("using this configuration:");
     println!("{config}");
   }
-  let (sender, receiver) = channel::create(); // cross-thread communication channel
+  enter_workspace(&config)?;
+  let (sender, mut receiver) = channel::create(); // cross-thread communication channel
   cli::ctrl_c::handle(sender.clone());
   let current_dir = env::current_dir().map_err(|err| UserError::CannotDetermineCurrentDirectory { err: err.to_string() })?;
-  Fifo::in_dir(&current_dir).listen(sender)?;
-  let mut last_command: Option<String> = None;
-  if debug {
-    println!("Contest is online in debug mode, Ctrl-C to exit");
-  } else {
-    println!("Contest is online, Ctrl-C to exit");
-  }
-  for signal in receiver {
-    match signal {
-      channel::Signal::ReceivedLine(line) => match run_with_decoration(line, config, debug, &mut last_command)? {
-        RunOutcome::ContinueTesting => {}
-        RunOutcome::Quit => break,
+  if mode == Mode::Watch {
+    Watcher::in_dir(&current_dir).listen(&config.options.watch, sender.clone())?;
+  }
+  if let Some(file) = &config.file {
+    ConfigWatcher::for_file(file).listen(sender.clone())?;
+  }
+  let socket = Socket::in_dir(&current_dir);
+  socket.listen(sender.clone())?;
+  let fifo = Fifo::in_dir(&current_dir);
+  if let Err(err) = fifo.listen(sender) {
+    let _ = socket.delete();
+    return Err(err);
+  }
+  match mode {
+    Mode::Execute => println!("Contest is online, Ctrl-C to exit"),
+    Mode::Debug => println!("Contest is online in debug mode, Ctrl-C to exit"),
+    Mode::Watch => println!("Contest is online and watching for file changes, Ctrl-C to exit"),
+  }
+  let result = process_signals(config, debug, &mut receiver);
+  // remove only the files that this server has created, other Contest instances might still use theirs
+  let _ = socket.delete();
+  let _ = fifo.delete();
+  result
+}
+
+/// executes the triggers and reacts to the events arriving through the given receiver until Contest should stop
+fn process_signals(mut config: Configuration, debug: bool, receiver: &mut channel::Receiver) -> Result<()> {
+  let mut last_command: Option<Command> = None;
+  while let Some(signal) = receiver.recv() {
+    let outcome = match signal {
+      channel::Signal::ReceivedLine { line, responder } => run_with_decoration(line, &responder, &config, debug, &mut last_command, receiver)?,
+      channel::Signal::FileChanged(file) => match config.watch_trigger(&file) {
+        Some(trigger) => {
+          print_before_run(&config);
+          run_trigger(&trigger, &Responder::default(), &config, &mut last_command, receiver)?
+        }
+        None => RunOutcome::ContinueTesting,
       },
+      channel::Signal::ConfigChanged => {
+        reload_config(&mut config, debug);
+        RunOutcome::ContinueTesting
+      }
       channel::Signal::Exit => break,
+    };
+    if outcome == RunOutcome::Quit {
+      break;
     }
   }
   println!("\nSee you later!");
   Ok(())
 }
 
-pub fn run_with_decoration(text: String, config: &config::Configuration, debug: bool, last_command: &mut Option<String>) -> Result<RunOutcome> {
+/// replaces the given configuration with the current content of its file,
+/// keeps the given configuration if the file content is invalid
+fn reload_config(config: &mut Configuration, debug: bool) {
+  let Some(file) = config.file.clone() else {
+    return;
+  };
+  match Configuration::load(&file) {
+    Ok(new_config) => {
+      *config = new_config;
+      println!("reloaded the configuration file");
+      if debug {
+        println!("using this configuration:");
+        println!("{config}");
+      }
+    }
+    Err(err) => {
+      cli::print_error(err);
+      println!("keeping the previous configuration");
+    }
+  }
+}
+
+/// reports all problems in the configuration file for the given directory
+pub fn validate(dir: &Path) -> Result<()> {
+  let Some(file) = Configuration::find(dir) else {
+    return Err(UserError::ConfigFileNotFound);
+  };
+  let problems = config::validate_file(&file)?;
+  for problem in &problems {
+    println!("{problem}");
+  }
+  let count = problems.iter().filter(|problem| problem.is_error()).count();
+  if count > 0 {
+    return Err(UserError::ConfigFileHasErrors { count });
+  }
+  println!("configuration file {} is valid", relative_path(&file, dir).display());
+  Ok(())
+}
+
+/// prints how the given trigger resolves into a command, without executing anything
+pub fn explain(text: String, config: &Configuration) -> Result<()> {
+  enter_workspace(config)?;
+  let trigger = Trigger::try_from(text)?;
+  println!("trigger: {trigger}");
+  config.explain(&trigger)
+}
+
+/// executes the given trigger once
+pub fn run_once(text: String, config: &Configuration) -> Result<()> {
+  enter_workspace(config)?;
+  let (sender, mut receiver) = channel::create();
+  cli::ctrl_c::handle(sender);
+  let mut last_command: Option<Command> = None;
+  let _ = run_with_decoration(text, &Responder::default(), config, false, &mut last_command, &mut receiver)?;
+  Ok(())
+}
+
+/// makes the directory containing the loaded configuration file the current directory,
+/// so that the FIFO, socket, and relative file paths in triggers resolve against it
+fn enter_workspace(config: &Configuration) -> Result<()> {
+  let (Some(file), Some(dir)) = (&config.file, config.dir()) else {
+    return Ok(());
+  };
+  let current_dir = env::current_dir().map_err(|err| UserError::CannotDetermineCurrentDirectory { err: err.to_string() })?;
+  println!("using configuration file {}", relative_path(file, &current_dir).display());
+  env::set_current_dir(dir).map_err(|err| UserError::CannotEnterDirectory {
+    path: dir.to_string_lossy().to_string(),
+    err: err.to_string(),
+  })
+}
+
+/// provides the path of the given config file as seen from the given directory below it
+fn relative_path(file: &Path, current_dir: &Path) -> PathBuf {
+  let Some(dir) = file.parent() else {
+    return file.to_path_buf();
+  };
+  let Ok(below) = current_dir.strip_prefix(dir) else {
+    return file.to_path_buf();
+  };
+  let mut result = PathBuf::new();
+  for _ in below.components().filter(|component| matches!(component, Component::Normal(_))) {
+    result.push("..");
+  }
+  result.join(file.file_name().unwrap_or_default())
+}
+
+fn run_with_decoration(
+  text: String,
+  responder: &Responder,
+  config: &config::Configuration,
+  debug: bool,
+  last_command: &mut Option<Command>,
+  receiver: &mut channel::Receiver,
+) -> Result<RunOutcome> {
   if debug {
     println!("received from client: {text}");
+    responder.send(Reply::Rejected {
+      error: S("Contest runs in debug mode and does not execute triggers"),
+      guidance: None,
+    });
     return Ok(RunOutcome::ContinueTesting);
   }
+  print_before_run(config);
+  let trigger = match Trigger::try_from(text) {
+    Ok(trigger) => trigger,
+    Err(err) if responder.has_client() => {
+      // client sent a malformed trigger --> let them know and keep serving the triggers that follow
+      cli::print_error(responder.reject(err));
+      return Ok(RunOutcome::ContinueTesting);
+    }
+    Err(err) => return Err(err),
+  };
+  if trigger == Trigger::Quit {
+    return Ok(RunOutcome::Quit);
+  }
+  run_trigger(&trigger, responder, config, last_command, receiver)
+}
+
+fn print_before_run(config: &config::Configuration) {
   for _ in 0..config.options.before_run.newlines {
     println!();
   }
   if config.options.before_run.clear_screen {
     print!("{esc}[2J{esc}[1;1H{esc}c", esc = 27 as char);
   }
-  let trigger = Trigger::try_from(text)?;
-  if trigger == Trigger::Quit {
-    return Ok(RunOutcome::Quit);
+}
+
+fn run_trigger(
+  trigger: &Trigger,
+  responder: &Responder,
+  config: &config::Configuration,
+  last_command: &mut Option<Command>,
+  receiver: &mut channel::Receiver,
+) -> Result<RunOutcome> {
+  let success = run_command(trigger, responder, config, last_command, receiver)?;
+  match success {
+    Outcome::Superseded => {
+      println!("superseded by a new trigger");
+      return Ok(RunOutcome::ContinueTesting);
+    }
+    Outcome::Aborted => return Ok(RunOutcome::Quit),
+    Outcome::TestPass | Outcome::TestFail | Outcome::TestTimeout => {}
   }
-  let success = run_command(&trigger, config, last_command)?;
   for _ in 0..config.options.after_run.newlines {
     println!();
   }
@@ -75,37 +263,175 @@ pub fn run_with_decoration(text: String, config: &config::Configuration, debug:
   Ok(RunOutcome::ContinueTesting)
 }
 
-fn run_command(trigger: &Trigger, configuration: &config::Configuration, last_command: &mut Option<String>) -> Result<subshell::Outcome> {
+fn run_command(
+  trigger: &Trigger,
+  responder: &Responder,
+  configuration: &config::Configuration,
+  last_command: &mut Option<Command>,
+  receiver: &mut channel::Receiver,
+) -> Result<subshell::Outcome> {
   let command = match configuration.get_command(trigger, last_command) {
-    Err(err) => match err {
-      UserError::NoCommandToRepeat => {
+    Err(err) => match responder.reject(err) {
+      err @ UserError::NoCommandToRepeat => {
         // repeat non-existing command --> don't stop, just print an error message and keep going
         cli::print_error(err);
         return Ok(subshell::Outcome::TestFail);
       }
-      UserError::TriggerRegexNotFound { regex: _, filename: _, line: _ } => {
+      err @ (UserError::TriggerRegexNotFound { regex: _, filename: _, line: _ } | UserError::FileRegexNotFound { regex: _, filename: _ }) => {
         // user triggered a command in a place where it doesn't match all regexes --> let them know and go to the correct location
         cli::print_error(err);
         return Ok(subshell::Outcome::TestFail);
       }
-      UserError::UnknownTrigger { source: _ } => {
+      err @ UserError::ProjectRootNotFound { file: _, markers: _ } => {
+        // user triggered a command for a file outside of any project --> let them know and keep running
+        cli::print_error(err);
+        return Ok(subshell::Outcome::TestFail);
+      }
+      err @ UserError::UnknownTrigger { source: _ } => {
         // user sent a trigger from the wrong file --> let them know and send one from the correct file
         cli::print_error(err);
         return Ok(subshell::Outcome::TestFail);
       }
-      _ => return Err(err),
+      err @ UserError::TemplateSyntax {
+        template: _,
+        column: _,
+        problem: _,
+      } => {
+        // a template in the configuration is broken --> let the user know and keep running so that they can fix the configuration
+        cli::print_error(err);
+        return Ok(subshell::Outcome::TestFail);
+      }
+      err @ UserError::UnresolvedPlaceholders { names: _, comment: _ } => {
+        // the action needs values that this trigger doesn't provide --> let the user know and keep running so that they can fix the configuration
+        cli::print_error(err);
+        return Ok(subshell::Outcome::TestFail);
+      }
+      err @ (UserError::VarCommandFailed { command: _, output: _ } | UserError::VarCommandTimeout { command: _, timeout: _ }) => {
+        // the command of a variable failed for this trigger --> let the user know and keep running
+        cli::print_error(err);
+        return Ok(subshell::Outcome::TestFail);
+      }
+      err => return Err(err),
     },
     Ok(command) => command,
   };
   last_command.replace(command.clone());
-  let result = subshell::run(&command)?;
+  responder.send(Reply::Accepted { command: command.to_string() });
+  let started = SystemTime::now();
+  let start_time = Instant::now();
+  let executions = match subshell::run_steps(&command, receiver, configuration.options.cancel_on_new_trigger, configuration.options.history > 0) {
+    Ok(executions) => executions,
+    Err(err) if responder.has_client() => {
+      // the test command cannot start --> let the client know and keep serving the triggers that follow
+      cli::print_error(responder.reject(err));
+      return Ok(subshell::Outcome::TestFail);
+    }
+    Err(err) => return Err(err),
+  };
+  let execution = subshell::combine(&executions);
+  responder.send(Reply::Finished {
+    outcome: execution.outcome.to_string(),
+  });
+  record_run(configuration, trigger, &command, started, start_time.elapsed(), &execution);
+  if command.run.len() > 1 {
+    print_step_results(&command, &executions);
+  }
   if configuration.options.after_run.print_result {
-    match &result {
+    match &execution.outcome {
       Outcome::TestPass => println!("SUCCESS"),
       Outcome::TestFail => println!("FAILED"),
+      Outcome::TestTimeout => println!("TIMEOUT"),
+      Outcome::Superseded | Outcome::Aborted => {}
+    }
+  }
+  Ok(execution.outcome)
+}
+
+/// lists the outcome of each step of the given command, including the steps that didn't run
+fn print_step_results(command: &Command, executions: &[Execution]) {
+  println!();
+  for (index, step) in command.run.iter().enumerate() {
+    let outcome = executions.get(index).map_or_else(|| S("skipped"), |execution| execution.outcome.to_string());
+    println!("step {}: {outcome:<10} {step}", index + 1);
+  }
+}
+
+/// adds the given test run to the history in the workspace
+fn record_run(configuration: &Configuration, trigger: &Trigger, command: &Command, started: SystemTime, duration: Duration, execution: &Execution) {
+  if configuration.options.history == 0 {
+    return;
+  }
+  let path = Path::new(history::FILE_NAME);
+  let run = Run {
+    id: 0,
+    trigger: trigger.to_string(),
+    command: command.to_string(),
+    started: started.duration_since(UNIX_EPOCH).map_or(0, |since_epoch| since_epoch.as_secs()),
+    duration: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+    exit_code: execution.exit_code,
+    outcome: execution.outcome.to_string(),
+    output: execution.output.clone(),
+  };
+  let result = History::load(path).and_then(|mut history| {
+    history.add(run, configuration.options.history);
+    history.save(path)
+  });
+  if let Err(err) = result {
+    cli::print_error(err);
+  }
+}
+
+/// lists the past test runs in the workspace of the given configuration, or shows the test run with the given id
+#[allow(clippy::str_to_string)]
+pub fn history(config: &Configuration, id: Option<usize>) -> Result<()> {
+  let path = config.dir().unwrap_or_else(|| Path::new(".")).join(history::FILE_NAME);
+  let history = History::load(&path)?;
+  let Some(id) = id else {
+    if history.runs.is_empty() {
+      println!("no test runs recorded yet");
+      return Ok(());
     }
+    let mut table = Table::new();
+    table.set_format(*FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.add_row(prettytable::row!["ID", "STARTED", "DURATION", "EXIT", "OUTCOME", "TRIGGER"]);
+    for run in &history.runs {
+      table.add_row(prettytable::row![
+        run.id,
+        format_started(run.started),
+        format_duration(run.duration),
+        run.exit_code.map_or_else(|| S("-"), |code| code.to_string()),
+        run.outcome,
+        run.trigger
+      ]);
+    }
+    table.printstd();
+    return Ok(());
+  };
+  let Some(run) = history.get(id) else {
+    return Err(UserError::HistoryRunNotFound { id });
+  };
+  println!("trigger: {}", run.trigger);
+  println!("command: {}", run.command);
+  println!("started: {}", format_started(run.started));
+  println!("duration: {}", format_duration(run.duration));
+  match run.exit_code {
+    Some(code) => println!("exit code: {code}"),
+    None => println!("exit code: -"),
   }
-  Ok(result)
+  println!("outcome: {}", run.outcome);
+  println!();
+  print!("{}", run.output);
+  Ok(())
+}
+
+/// formats the given start time of a test run, provided in seconds since the Unix epoch
+fn format_started(started: u64) -> String {
+  humantime::format_rfc3339_seconds(UNIX_EPOCH + Duration::from_secs(started)).to_string()
+}
+
+/// formats the given duration of a test run, provided in milliseconds
+fn format_duration(duration: u64) -> String {
+  format!("{:.1?}", Duration::from_millis(duration))
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -1,8 +1,7 @@
 use contest::cli::{self, Command};
-use contest::client::fifo;
 use contest::config::Configuration;
-use contest::{Result, listen, run_with_decoration};
-use std::fs;
+use contest::{Result, UserError, explain, history, listen, run_once, validate, watch};
+use std::env;
 use std::process::ExitCode;
 
 fn main() -> ExitCode {
@@ -11,21 +10,23 @@ fn main() -> ExitCode {
     cli::print_error(err);
     exit_code = ExitCode::FAILURE;
   }
-  let _ = fs::remove_file(fifo::FILE_NAME);
   exit_code
 }
 
 fn main_with_result() -> Result<()> {
-  let config = Configuration::read()?;
+  let current_dir = env::current_dir().map_err(|err| UserError::CannotDetermineCurrentDirectory { err: err.to_string() })?;
   match Command::parse() {
-    Command::Start => listen(&config, false),
-    Command::Debug => listen(&config, true),
+    Command::Start => listen(Configuration::read(&current_dir)?, false),
+    Command::Debug => listen(Configuration::read(&current_dir)?, true),
     Command::Run { trigger } => {
+      let config = Configuration::read(&current_dir)?;
       println!("running trigger: {trigger}");
-      let mut last_command: Option<String> = None;
-      let _ = run_with_decoration(trigger, &config, false, &mut last_command)?;
-      Ok(())
+      run_once(trigger, &config)
     }
+    Command::History { id } => history(&Configuration::read(&current_dir)?, id),
     Command::Init => Configuration::create(),
+    Command::Explain { trigger } => explain(trigger, &Configuration::read(&current_dir)?),
+    Command::Validate => validate(&current_dir),
+    Command::Watch => watch(Configuration::read(&current_dir)?),
   }
 }
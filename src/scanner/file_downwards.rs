@@ -0,0 +1,18 @@
+use super::{Match, read_file, string_downwards};
+use crate::{Result, UserError};
+use regex::Regex;
+
+/// provides the first match of the given `Regex`
+/// in the content of the file with the given path
+/// starting at the given 1-based line and scanning towards the file end
+pub fn file_downwards(file_path: &str, re: &Regex, line: usize) -> Result<Match> {
+  let file_content = read_file(file_path)?;
+  let Some(result) = string_downwards(&file_content, re, line)? else {
+    return Err(UserError::TriggerRegexNotFound {
+      regex: re.to_string(),
+      filename: file_path.to_owned(),
+      line,
+    });
+  };
+  Ok(result)
+}
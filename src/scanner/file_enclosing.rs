@@ -0,0 +1,17 @@
+use super::{Match, read_file, string_enclosing};
+use crate::{Result, UserError};
+use regex::Regex;
+
+/// provides the matches of the given `Regex` for all scopes that enclose the given 1-based line
+/// in the content of the file with the given path, joined by the given separator
+pub fn file_enclosing(file_path: &str, re: &Regex, line: usize, separator: &str) -> Result<Match> {
+  let file_content = read_file(file_path)?;
+  let Some(result) = string_enclosing(&file_content, re, line, separator)? else {
+    return Err(UserError::TriggerRegexNotFound {
+      regex: re.to_string(),
+      filename: file_path.to_owned(),
+      line,
+    });
+  };
+  Ok(result)
+}
@@ -0,0 +1,16 @@
+use super::{Match, read_file, string_first};
+use crate::{Result, UserError};
+use regex::Regex;
+
+/// provides the first match of the given `Regex`
+/// in the content of the file with the given path
+pub fn file_first(file_path: &str, re: &Regex) -> Result<Match> {
+  let file_content = read_file(file_path)?;
+  let Some(result) = string_first(&file_content, re)? else {
+    return Err(UserError::FileRegexNotFound {
+      regex: re.to_string(),
+      filename: file_path.to_owned(),
+    });
+  };
+  Ok(result)
+}
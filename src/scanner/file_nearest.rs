@@ -0,0 +1,18 @@
+use super::{Match, read_file, string_nearest};
+use crate::{Result, UserError};
+use regex::Regex;
+
+/// provides the match of the given `Regex`
+/// in the content of the file with the given path
+/// that is closest to the given 1-based line
+pub fn file_nearest(file_path: &str, re: &Regex, line: usize) -> Result<Match> {
+  let file_content = read_file(file_path)?;
+  let Some(result) = string_nearest(&file_content, re, line)? else {
+    return Err(UserError::TriggerRegexNotFound {
+      regex: re.to_string(),
+      filename: file_path.to_owned(),
+      line,
+    });
+  };
+  Ok(result)
+}
@@ -0,0 +1,19 @@
+use super::{Match, read_file, string_range};
+use crate::{Result, UserError};
+use regex::Regex;
+
+/// provides all matches of the given `Regex`
+/// in the content of the file with the given path
+/// between the given 1-based lines, including the match that encloses the first line
+pub fn file_range(file_path: &str, re: &Regex, line: usize, end_line: usize) -> Result<Vec<Match>> {
+  let file_content = read_file(file_path)?;
+  let result = string_range(&file_content, re, line, end_line)?;
+  if result.is_empty() {
+    return Err(UserError::TriggerRegexNotFound {
+      regex: re.to_string(),
+      filename: file_path.to_owned(),
+      line,
+    });
+  }
+  Ok(result)
+}
@@ -1,16 +1,12 @@
-use super::string_upwards;
+use super::{Match, read_file, string_upwards};
 use crate::{Result, UserError};
 use regex::Regex;
-use std::fs;
 
 /// provides the first match of the given `Regex`
 /// in the content of the file with the given path
 /// starting at the given index and scanning towards the file beginning
-pub fn file_upwards(file_path: &str, re: &Regex, index: usize) -> Result<String> {
-  let file_content = fs::read_to_string(file_path).map_err(|err| UserError::CannotReadFile {
-    path: file_path.to_owned(),
-    err: err.to_string(),
-  })?;
+pub fn file_upwards(file_path: &str, re: &Regex, index: usize) -> Result<Match> {
+  let file_content = read_file(file_path)?;
   let Some(result) = string_upwards(&file_content, re, index)? else {
     return Err(UserError::TriggerRegexNotFound {
       regex: re.to_string(),
@@ -1,7 +1,103 @@
 //! finds regular expression matches in the content of files on disk
 
+mod file_downwards;
+mod file_enclosing;
+mod file_first;
+mod file_nearest;
+mod file_range;
 mod file_upwards;
+mod string_downwards;
+mod string_enclosing;
+mod string_first;
+mod string_nearest;
+mod string_range;
 mod string_upwards;
 
+use crate::{Result, UserError};
+pub use file_downwards::file_downwards;
+pub use file_enclosing::file_enclosing;
+pub use file_first::file_first;
+pub use file_nearest::file_nearest;
+pub use file_range::file_range;
 pub use file_upwards::file_upwards;
+use regex::Regex;
+use std::fs;
+pub use string_downwards::string_downwards;
+pub use string_enclosing::string_enclosing;
+pub use string_first::string_first;
+pub use string_nearest::string_nearest;
+pub use string_range::string_range;
 pub use string_upwards::string_upwards;
+
+/// a regex capture found in the content of a file
+#[derive(Debug, Eq, PartialEq)]
+pub struct Match {
+  /// the captured text
+  pub text: String,
+  /// the 1-based number of the line containing the capture
+  pub line: usize,
+  /// the names and values of the named capture groups
+  pub groups: Vec<(String, String)>,
+}
+
+/// the captures of a regex in a line of text
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Capture {
+  /// the text of the first capture group
+  pub text: String,
+  /// the names and values of the named capture groups, in the order in which they appear in the regex
+  pub groups: Vec<(String, String)>,
+}
+
+/// provides the captures of the given regex in the given line of text.
+/// Regexes without named groups may only contain one capture group.
+pub fn capture(line_text: &str, re: &Regex) -> Result<Option<Capture>> {
+  let Some(captures) = re.captures(line_text) else {
+    return Ok(None);
+  };
+  if !captures.iter().skip(1).any(|group| group.is_some()) {
+    return Ok(None);
+  }
+  let groups: Vec<(String, String)> = re
+    .capture_names()
+    .flatten()
+    .map(|name| (name.to_owned(), captures.name(name).map(|group| group.as_str().to_owned()).unwrap_or_default()))
+    .collect();
+  if groups.is_empty() && captures.len() > 2 {
+    // without named groups, we should get only 2 captures: one for the entire string, and one for the capture
+    return Err(UserError::TriggerTooManyCaptures {
+      count: captures.len(),
+      regex: re.to_string(),
+      line: line_text.to_owned(),
+    });
+  }
+  Ok(Some(Capture {
+    text: captures.get(1).map(|group| group.as_str().to_owned()).unwrap_or_default(),
+    groups,
+  }))
+}
+
+/// provides the capture of the given regex in the line with the given 1-based number
+fn capture_line(lines: &[&str], re: &Regex, line: usize) -> Result<Option<Match>> {
+  let Some(line_text) = line.checked_sub(1).and_then(|index| lines.get(index)) else {
+    return Ok(None);
+  };
+  Ok(capture(line_text, re)?.map(|capture| Match {
+    text: capture.text,
+    line,
+    groups: capture.groups,
+  }))
+}
+
+/// provides the number of whitespace characters at the beginning of the given line
+fn indentation(line: &str) -> usize {
+  line.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+/// provides the content of the file with the given path
+fn read_file(file_path: &str) -> Result<String> {
+  fs::read_to_string(file_path).map_err(|err| UserError::CannotReadFile {
+    path: file_path.to_owned(),
+    err: err.to_string(),
+  })
+}
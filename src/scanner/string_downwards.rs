@@ -0,0 +1,80 @@
+use super::{Match, capture_line};
+use crate::Result;
+use regex::Regex;
+
+/// provides the first capture of the given regex in the given string
+/// starting at the line with the given 1-based number and scanning towards the end of the file
+pub fn string_downwards(text: &str, re: &Regex, line: usize) -> Result<Option<Match>> {
+  let lines: Vec<&str> = text.split('\n').collect();
+  for line in line.max(1)..=lines.len() {
+    if let Some(found) = capture_line(&lines, re, line)? {
+      return Ok(Some(found));
+    }
+  }
+  Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Match;
+  use super::string_downwards;
+  use big_s::S;
+
+  #[test]
+  fn match_on_the_given_line() {
+    let text = r#"#[test]
+fn test_func() {
+  println!("hello");
+}
+"#;
+    let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
+    let have = string_downwards(text, &re, 2).unwrap().unwrap();
+    let want = Match {
+      text: S("test_func"),
+      line: 2,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn match_on_the_line_below() {
+    let text = r#"#[test]
+fn test_func() {
+  println!("hello");
+}
+"#;
+    let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
+    let have = string_downwards(text, &re, 1).unwrap().unwrap();
+    let want = Match {
+      text: S("test_func"),
+      line: 2,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn match_on_the_last_line() {
+    let text = "one\ntwo\nfn test_func()";
+    let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
+    let have = string_downwards(text, &re, 1).unwrap().unwrap();
+    let want = Match {
+      text: S("test_func"),
+      line: 3,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn match_above_only() {
+    let text = r#"fn test_func() {
+  println!("hello");
+}
+"#;
+    let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
+    let have = string_downwards(text, &re, 2).unwrap();
+    assert!(have.is_none());
+  }
+}
@@ -0,0 +1,134 @@
+use super::{Capture, Match, capture, indentation};
+use crate::Result;
+use regex::Regex;
+
+/// provides the captures of the given regex for all scopes in the given string
+/// that enclose the line with the given 1-based number, from the outermost to the innermost scope,
+/// joined by the given separator.
+///
+/// Scopes are detected via indentation: scanning upwards from the given line,
+/// only lines that are less indented than all lines seen so far can start an enclosing scope.
+/// The line and named groups of the returned match are those of the innermost scope.
+pub fn string_enclosing(text: &str, re: &Regex, line: usize, separator: &str) -> Result<Option<Match>> {
+  let lines: Vec<&str> = text.split('\n').collect();
+  let mut found: Vec<(Capture, usize)> = vec![];
+  // lines must be less indented than this to start an enclosing scope
+  let mut max_indent = usize::MAX;
+  for line in (1..=line.min(lines.len())).rev() {
+    let line_text = lines[line - 1];
+    if line_text.trim().is_empty() {
+      continue;
+    }
+    let indent = indentation(line_text);
+    if indent >= max_indent {
+      // this line is inside a sibling scope or at the same level as the current scope
+      continue;
+    }
+    max_indent = indent;
+    if let Some(capture) = capture(line_text, re)? {
+      found.push((capture, line));
+    }
+    if indent == 0 {
+      break;
+    }
+  }
+  if found.is_empty() {
+    return Ok(None);
+  }
+  let names: Vec<&str> = found.iter().rev().map(|(capture, _)| capture.text.as_str()).collect();
+  let text = names.join(separator);
+  let (innermost, line) = found.swap_remove(0);
+  Ok(Some(Match {
+    text,
+    line,
+    groups: innermost.groups,
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Match;
+  use super::string_enclosing;
+  use big_s::S;
+
+  const RUST: &str = r"mod tests {
+  mod parse {
+    #[test]
+    fn empty() {
+    }
+
+    #[test]
+    fn valid() {
+      assert!(true);
+    }
+  }
+}
+";
+
+  #[test]
+  fn nested_modules() {
+    let re = regex::Regex::new(r"^\s*(?:mod|fn) (\w+)").unwrap();
+    let have = string_enclosing(RUST, &re, 9, "::").unwrap().unwrap();
+    let want = Match {
+      text: S("tests::parse::valid"),
+      line: 8,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn on_the_matching_line() {
+    let re = regex::Regex::new(r"^\s*(?:mod|fn) (\w+)").unwrap();
+    let have = string_enclosing(RUST, &re, 4, "::").unwrap().unwrap();
+    let want = Match {
+      text: S("tests::parse::empty"),
+      line: 4,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn skips_sibling_scopes() {
+    let re = regex::Regex::new(r"^\s*(?:mod|fn) (\w+)").unwrap();
+    let have = string_enclosing(RUST, &re, 7, "::").unwrap().unwrap();
+    let want = Match {
+      text: S("tests::parse"),
+      line: 2,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn javascript() {
+    let text = r#"describe("Outer", () => {
+  it("first test", () => {
+    expect(1).toBe(1)
+  })
+  helper(() => {
+    it("ignored", () => {})
+  })
+  it("inner test", () => {
+    expect(2).toBe(2)
+  })
+})
+"#;
+    let re = regex::Regex::new(r#"(?:describe|it)\("([^"]+)""#).unwrap();
+    let have = string_enclosing(text, &re, 9, " > ").unwrap().unwrap();
+    let want = Match {
+      text: S("Outer > inner test"),
+      line: 8,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn no_match() {
+    let re = regex::Regex::new(r"^\s*class (\w+)").unwrap();
+    let have = string_enclosing(RUST, &re, 9, "::").unwrap();
+    assert!(have.is_none());
+  }
+}
@@ -0,0 +1,64 @@
+use super::{Match, capture_line};
+use crate::Result;
+use regex::Regex;
+
+/// provides the first capture of the given regex in the given string,
+/// scanning from the beginning of the file
+pub fn string_first(text: &str, re: &Regex) -> Result<Option<Match>> {
+  let lines: Vec<&str> = text.split('\n').collect();
+  for line in 1..=lines.len() {
+    if let Some(found) = capture_line(&lines, re, line)? {
+      return Ok(Some(found));
+    }
+  }
+  Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Match;
+  use super::string_first;
+  use big_s::S;
+
+  #[test]
+  fn match_on_the_first_line() {
+    let text = "package parser\n\nimport \"testing\"\n";
+    let re = regex::Regex::new("^package (\\w+)").unwrap();
+    let have = string_first(text, &re).unwrap().unwrap();
+    let want = Match {
+      text: S("parser"),
+      line: 1,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn multiple_matches() {
+    let text = "// comment\nmodule one\nmodule two\n";
+    let re = regex::Regex::new("^module (\\w+)").unwrap();
+    let have = string_first(text, &re).unwrap().unwrap();
+    let want = Match {
+      text: S("one"),
+      line: 2,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn no_match() {
+    let text = "placeholder\n";
+    let re = regex::Regex::new("^package (\\w+)").unwrap();
+    let have = string_first(text, &re).unwrap();
+    assert!(have.is_none());
+  }
+
+  #[test]
+  fn too_many_captures() {
+    let text = "package parser\n";
+    let re = regex::Regex::new("^(package) (\\w+)").unwrap();
+    let have = string_first(text, &re);
+    assert!(have.is_err());
+  }
+}
@@ -0,0 +1,95 @@
+use super::{Match, capture_line};
+use crate::Result;
+use regex::Regex;
+
+/// provides the capture of the given regex in the given string
+/// that is closest to the line with the given 1-based number,
+/// preferring the match above if two matches are equally close
+pub fn string_nearest(text: &str, re: &Regex, line: usize) -> Result<Option<Match>> {
+  let lines: Vec<&str> = text.split('\n').collect();
+  for distance in 0..=line.max(lines.len()) {
+    if let Some(above) = line.checked_sub(distance)
+      && let Some(found) = capture_line(&lines, re, above)?
+    {
+      return Ok(Some(found));
+    }
+    if distance > 0
+      && let Some(found) = capture_line(&lines, re, line + distance)?
+    {
+      return Ok(Some(found));
+    }
+  }
+  Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Match;
+  use super::string_nearest;
+  use big_s::S;
+
+  const TEXT: &str = r"fn one() {
+}
+
+// marker
+
+
+fn two() {
+}
+";
+
+  #[test]
+  fn match_on_the_given_line() {
+    let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
+    let have = string_nearest(TEXT, &re, 7).unwrap().unwrap();
+    let want = Match {
+      text: S("two"),
+      line: 7,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn closer_above() {
+    let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
+    let have = string_nearest(TEXT, &re, 3).unwrap().unwrap();
+    let want = Match {
+      text: S("one"),
+      line: 1,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn closer_below() {
+    let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
+    let have = string_nearest(TEXT, &re, 5).unwrap().unwrap();
+    let want = Match {
+      text: S("two"),
+      line: 7,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn equal_distance() {
+    let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
+    let have = string_nearest(TEXT, &re, 4).unwrap().unwrap();
+    let want = Match {
+      text: S("one"),
+      line: 1,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn no_match() {
+    let re = regex::Regex::new("class (\\w+)").unwrap();
+    let have = string_nearest(TEXT, &re, 4).unwrap();
+    assert!(have.is_none());
+  }
+}
@@ -0,0 +1,131 @@
+use super::{Match, capture_line, indentation};
+use crate::Result;
+use regex::Regex;
+
+/// provides all captures of the given regex in the given string
+/// between the lines with the given 1-based numbers,
+/// starting with the capture at or above the first line that encloses the start of the range.
+///
+/// Like for enclosing scopes, the capture above the range encloses it only if no line in between
+/// is indented as little as the captured line, as the closing brace of a test that ended would be.
+pub fn string_range(text: &str, re: &Regex, line: usize, end_line: usize) -> Result<Vec<Match>> {
+  let lines: Vec<&str> = text.split('\n').collect();
+  let mut result = vec![];
+  // lines must be less indented than this to start the enclosing test
+  let mut max_indent = usize::MAX;
+  for enclosing_line in (1..=line.min(lines.len())).rev() {
+    let line_text = lines[enclosing_line - 1];
+    if line_text.trim().is_empty() {
+      continue;
+    }
+    let indent = indentation(line_text);
+    if indent >= max_indent {
+      continue;
+    }
+    max_indent = indent;
+    if let Some(found) = capture_line(&lines, re, enclosing_line)? {
+      result.push(found);
+      break;
+    }
+    if indent == 0 {
+      break;
+    }
+  }
+  for line in line + 1..=end_line.min(lines.len()) {
+    if let Some(found) = capture_line(&lines, re, line)? {
+      result.push(found);
+    }
+  }
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Match;
+  use super::string_range;
+
+  const TEXT: &str = r"use super::*;
+
+#[test]
+fn one() {
+  assert!(true);
+}
+
+#[test]
+fn two() {
+  assert!(true);
+}
+
+#[test]
+fn three() {
+  assert!(true);
+}
+";
+
+  fn found(text: &str, line: usize) -> Match {
+    Match {
+      text: text.to_owned(),
+      line,
+      groups: vec![],
+    }
+  }
+
+  #[test]
+  fn starts_inside_a_test() {
+    let re = regex::Regex::new(r"fn (\w+)\(").unwrap();
+    let have = string_range(TEXT, &re, 5, 10).unwrap();
+    let want = vec![found("one", 4), found("two", 9)];
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn starts_on_a_test() {
+    let re = regex::Regex::new(r"fn (\w+)\(").unwrap();
+    let have = string_range(TEXT, &re, 9, 17).unwrap();
+    let want = vec![found("two", 9), found("three", 14)];
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn starts_between_two_tests() {
+    let re = regex::Regex::new(r"fn (\w+)\(").unwrap();
+    let have = string_range(TEXT, &re, 7, 10).unwrap();
+    let want = vec![found("two", 9)];
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn starts_between_two_nested_tests() {
+    let text = r"mod tests {
+  #[test]
+  fn one() {
+    assert!(true);
+  }
+
+  #[test]
+  fn two() {
+    assert!(true);
+  }
+}
+";
+    let re = regex::Regex::new(r"fn (\w+)\(").unwrap();
+    let have = string_range(text, &re, 6, 9).unwrap();
+    let want = vec![found("two", 8)];
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn end_beyond_the_text() {
+    let re = regex::Regex::new(r"fn (\w+)\(").unwrap();
+    let have = string_range(TEXT, &re, 10, 100).unwrap();
+    let want = vec![found("two", 9), found("three", 14)];
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn no_match() {
+    let re = regex::Regex::new(r"fn (\w+)\(").unwrap();
+    let have = string_range(TEXT, &re, 1, 2).unwrap();
+    assert!(have.is_empty());
+  }
+}
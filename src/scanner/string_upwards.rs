@@ -1,38 +1,33 @@
-use crate::{Result, UserError};
+use super::{Match, capture};
+use crate::Result;
 use regex::Regex;
 
 /// provides the first capture of the given regex in the given string
 /// starting at the given line and scanning towards the beginning of the file
-pub fn string_upwards(text: &str, re: &Regex, mut index: usize) -> Result<Option<String>> {
+pub fn string_upwards(text: &str, re: &Regex, mut index: usize) -> Result<Option<Match>> {
   let lines: Vec<&str> = text.split('\n').collect();
   while index > 0 {
     let Some(line_text) = lines.get(index) else {
       return Ok(None);
     };
+    let line = index + 1;
     index -= 1;
-    let Some(captures) = re.captures(line_text) else {
-      // no match on this line --> try the one above
-      continue;
-    };
-    let Some(match_1) = captures.get(1) else {
-      continue;
-    };
-    if captures.len() > 2 {
-      // we should get only 2 captures: one for the entire string, and one for the capture
-      return Err(UserError::TriggerTooManyCaptures {
-        count: captures.len(),
-        regex: re.to_string(),
-        line: (*line_text).to_owned(),
-      });
+    if let Some(capture) = capture(line_text, re)? {
+      return Ok(Some(Match {
+        text: capture.text,
+        line,
+        groups: capture.groups,
+      }));
     }
-    return Ok(Some(match_1.as_str().to_owned()));
   }
   Ok(None)
 }
 
 #[cfg(test)]
 mod tests {
+  use super::super::Match;
   use super::string_upwards;
+  use big_s::S;
 
   #[test]
   fn match_on_the_given_line() {
@@ -45,7 +40,11 @@ pub fn test_func(param: &str) -> String {
 "#;
     let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
     let have = string_upwards(text, &re, 3).unwrap().unwrap();
-    let want = "test_func";
+    let want = Match {
+      text: S("test_func"),
+      line: 4,
+      groups: vec![],
+    };
     assert_eq!(have, want);
   }
 
@@ -60,7 +59,11 @@ pub fn test_func(param: &str) -> String {
 "#;
     let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
     let have = string_upwards(text, &re, 4).unwrap().unwrap();
-    let want = "test_func";
+    let want = Match {
+      text: S("test_func"),
+      line: 4,
+      groups: vec![],
+    };
     assert_eq!(have, want);
   }
 
@@ -73,7 +76,28 @@ pub fn test_func(param: &str) -> String {
 "#;
     let re = regex::Regex::new("fn (\\w+?)\\(").unwrap();
     let have = string_upwards(text, &re, 3).unwrap().unwrap();
-    let want = "test_func";
+    let want = Match {
+      text: S("test_func"),
+      line: 2,
+      groups: vec![],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn named_groups() {
+    let text = r"\
+class TestParser:
+    def test_empty(self):
+        pass
+";
+    let re = regex::Regex::new(r"def (?<method>\w+)\((?<arg>\w+)\)").unwrap();
+    let have = string_upwards(text, &re, 3).unwrap().unwrap();
+    let want = Match {
+      text: S("test_empty"),
+      line: 3,
+      groups: vec![(S("method"), S("test_empty")), (S("arg"), S("self"))],
+    };
     assert_eq!(have, want);
   }
 
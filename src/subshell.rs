@@ -1,30 +1,579 @@
 //! runs commands in a subshell
 
+use crate::channel::{self, Signal};
+use crate::config::{Command, ParallelOutput};
 use crate::{Result, UserError};
-use std::process::Command;
+use nix::sys::signal::{self as nix_signal, killpg};
+use nix::unistd::Pid;
+use std::fmt::{self, Display};
+use std::io::{self, Read, Write};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{self, Child, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+/// how often to check for new signals while a test is running
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// how long a timed out test gets to shut down before it gets killed
+const TIMEOUT_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// how many bytes of the most recent output of each step get captured
+const MAX_CAPTURED_OUTPUT: usize = 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Outcome {
   TestPass,
   TestFail,
+  /// the test did not finish within its timeout
+  TestTimeout,
+  /// the test run was canceled because a new trigger arrived
+  Superseded,
+  /// the test run was canceled because Contest is shutting down
+  Aborted,
 }
 
-pub fn run(command: &str) -> Result<Outcome> {
-  println!("executing: {command}");
-  let words = shellwords::split(command).map_err(|err| UserError::CannotSplitShellString {
-    source: command.to_owned(),
+impl Display for Outcome {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Outcome::TestPass => "pass",
+      Outcome::TestFail => "fail",
+      Outcome::TestTimeout => "timeout",
+      Outcome::Superseded => "superseded",
+      Outcome::Aborted => "aborted",
+    })
+  }
+}
+
+/// the result of running a command
+pub struct Execution {
+  pub outcome: Outcome,
+  /// the exit code of the command, if it exited on its own
+  pub exit_code: Option<i32>,
+  /// everything the command printed to stdout and stderr
+  pub output: String,
+}
+
+/// runs the steps of the given command, one after the other or all at the same time
+///
+/// Sequential steps stop after the first step that doesn't pass, unless the command continues on errors.
+/// Canceled steps always stop the remaining steps.
+/// Provides the executions of the steps that ran.
+/// Sequential steps print straight to the terminal unless `capture` is enabled.
+pub fn run_steps(command: &Command, receiver: &mut channel::Receiver, cancel_on_new_trigger: bool, capture: bool) -> Result<Vec<Execution>> {
+  if command.parallel {
+    return run_parallel(command, receiver, cancel_on_new_trigger);
+  }
+  let mut executions = Vec::with_capacity(command.run.len());
+  for step in &command.run {
+    println!("executing: {step}");
+    let mut running = if capture {
+      [spawn(command, step, Some((io::stdout(), io::stderr())))?]
+    } else {
+      [spawn::<io::Stdout, io::Stderr>(command, step, None)?]
+    };
+    wait(command, &mut running, receiver, cancel_on_new_trigger)?;
+    let [running] = running;
+    let execution = running.finish();
+    let outcome = execution.outcome;
+    executions.push(execution);
+    match outcome {
+      Outcome::TestPass => {}
+      Outcome::TestFail | Outcome::TestTimeout if command.continue_on_error => {}
+      Outcome::TestFail | Outcome::TestTimeout | Outcome::Superseded | Outcome::Aborted => break,
+    }
+  }
+  Ok(executions)
+}
+
+/// runs all steps of the given command at the same time and waits for them to finish
+fn run_parallel(command: &Command, receiver: &mut channel::Receiver, cancel_on_new_trigger: bool) -> Result<Vec<Execution>> {
+  let mut running = Vec::with_capacity(command.run.len());
+  for (index, step) in command.run.iter().enumerate() {
+    println!("executing: {step}");
+    let spawned = match command.parallel_output {
+      ParallelOutput::Grouped => spawn(command, step, Some((io::sink(), io::sink()))),
+      ParallelOutput::Prefixed => {
+        let prefix = format!("[{}] ", index + 1);
+        spawn(
+          command,
+          step,
+          Some((PrefixedLines::new(prefix.clone(), io::stdout()), PrefixedLines::new(prefix, io::stderr()))),
+        )
+      }
+    };
+    match spawned {
+      Ok(step) => running.push(step),
+      Err(err) => {
+        for step in &mut running {
+          kill(&mut step.child);
+        }
+        return Err(err);
+      }
+    }
+  }
+  wait(command, &mut running, receiver, cancel_on_new_trigger)?;
+  let executions: Vec<Execution> = running.into_iter().map(Running::finish).collect();
+  if command.parallel_output == ParallelOutput::Grouped {
+    for (index, (step, execution)) in command.run.iter().zip(&executions).enumerate() {
+      println!("--- step {}: {step} ---", index + 1);
+      print!("{}", execution.output);
+      if !execution.output.is_empty() && !execution.output.ends_with('\n') {
+        println!();
+      }
+    }
+  }
+  Ok(executions)
+}
+
+/// combines the executions of several steps into the execution of the entire command
+///
+/// A canceled step cancels the entire command.
+/// Otherwise the outcome and exit code come from the first step that didn't pass, or else from the last step.
+pub fn combine(executions: &[Execution]) -> Execution {
+  let decisive = executions
+    .iter()
+    .find(|execution| matches!(execution.outcome, Outcome::Superseded | Outcome::Aborted))
+    .or_else(|| executions.iter().find(|execution| execution.outcome != Outcome::TestPass))
+    .or_else(|| executions.last());
+  Execution {
+    outcome: decisive.map_or(Outcome::TestPass, |execution| execution.outcome),
+    exit_code: decisive.and_then(|execution| execution.exit_code),
+    output: executions.iter().map(|execution| execution.output.as_str()).collect(),
+  }
+}
+
+/// a step that has been started
+struct Running {
+  child: Child,
+  /// the threads that copy the output of the step
+  tees: Vec<JoinHandle<()>>,
+  /// the most recent output the step printed to stdout and stderr so far
+  output: Arc<Mutex<Captured>>,
+  /// the outcome and exit code, once the step has ended
+  ended: Option<(Outcome, Option<i32>)>,
+}
+
+impl Running {
+  /// provides the execution of this step after it has ended
+  fn finish(self) -> Execution {
+    // wait until all output has arrived
+    for tee in self.tees {
+      let _ = tee.join();
+    }
+    let output = self.output.lock().map(|output| output.text()).unwrap_or_default();
+    let (outcome, exit_code) = self.ended.unwrap_or((Outcome::Aborted, None));
+    Execution { outcome, exit_code, output }
+  }
+}
+
+/// starts the given step of the given command
+///
+/// If terminal streams are given, the output of the step goes to them and gets captured at the same time.
+/// Otherwise the step prints directly to the terminal.
+fn spawn<O: Write + Send + 'static, E: Write + Send + 'static>(command: &Command, step: &str, streams: Option<(O, E)>) -> Result<Running> {
+  let words = match &command.shell {
+    // let the shell interpret the command
+    Some(shell) => [shell.program.clone()].into_iter().chain(shell.args.iter().cloned()).chain([step.to_owned()]).collect(),
+    None => shellwords::split(step).map_err(|err| UserError::CannotSplitShellString {
+      source: step.to_owned(),
+      err: err.to_string(),
+    })?,
+  };
+  let Some((cmd, args)) = words.split_first() else {
+    return Err(UserError::RunCommandIsEmpty);
+  };
+  let mut process = process::Command::new(cmd);
+  if let Some(cwd) = &command.cwd {
+    if !Path::new(cwd).is_dir() {
+      return Err(UserError::RunDirectoryNotFound { path: cwd.clone() });
+    }
+    process.current_dir(cwd);
+  }
+  // run the command in its own process group so that we can stop it together with all its subprocesses.
+  // Processes in a background process group that read from the terminal get stopped, so the command gets no input.
+  let (stdout_pipe, stderr_pipe) = if streams.is_some() {
+    (Stdio::piped(), Stdio::piped())
+  } else {
+    (Stdio::inherit(), Stdio::inherit())
+  };
+  let Ok(mut child) = process
+    .args(args)
+    .envs(&command.env)
+    .process_group(0)
+    .stdin(Stdio::null())
+    .stdout(stdout_pipe)
+    .stderr(stderr_pipe)
+    .spawn()
+  else {
+    return Err(UserError::RunCommandNotFound { command: cmd.clone() });
+  };
+  let output = Arc::new(Mutex::new(Captured::new(MAX_CAPTURED_OUTPUT)));
+  let tees: Vec<JoinHandle<()>> = match streams {
+    Some((stdout, stderr)) => [
+      child.stdout.take().map(|source| tee(source, stdout, Arc::clone(&output))),
+      child.stderr.take().map(|source| tee(source, stderr, Arc::clone(&output))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect(),
+    None => vec![],
+  };
+  Ok(Running { child, tees, output, ended: None })
+}
+
+/// waits until all given steps have ended
+///
+/// While the steps run, newly received triggers either cancel them (if `cancel_on_new_trigger` is enabled)
+/// or get postponed until the steps have finished.
+fn wait(command: &Command, running: &mut [Running], receiver: &mut channel::Receiver, cancel_on_new_trigger: bool) -> Result<()> {
+  let start_time = Instant::now();
+  loop {
+    for step in running.iter_mut().filter(|step| step.ended.is_none()) {
+      match step.child.try_wait() {
+        Ok(Some(exit_status)) => {
+          let outcome = if exit_status.success() { Outcome::TestPass } else { Outcome::TestFail };
+          step.ended = Some((outcome, exit_status.code()));
+        }
+        Ok(None) => {}
+        Err(err) => {
+          end_all(running, Outcome::Aborted, stop);
+          return Err(UserError::CannotWaitForCommand { err: err.to_string() });
+        }
+      }
+    }
+    if running.iter().all(|step| step.ended.is_some()) {
+      return Ok(());
+    }
+    if command.timeout.is_some_and(|timeout| start_time.elapsed() >= timeout) {
+      end_all(running, Outcome::TestTimeout, terminate);
+      return Ok(());
+    }
+    match receiver.recv_timeout(POLL_INTERVAL) {
+      None => {}
+      Some(Signal::Exit) => {
+        end_all(running, Outcome::Aborted, stop);
+        return Ok(());
+      }
+      Some(Signal::ConfigChanged) => receiver.postpone(Signal::ConfigChanged),
+      Some(signal) => {
+        if cancel_on_new_trigger {
+          end_all(running, Outcome::Superseded, stop);
+          receiver.put_back(signal);
+          return Ok(());
+        }
+        receiver.postpone(signal);
+      }
+    }
+  }
+}
+
+/// ends the given steps that are still running with the given outcome, using the given way to stop their processes
+fn end_all(running: &mut [Running], outcome: Outcome, end: fn(&mut [&mut Child])) {
+  let mut unfinished: Vec<&mut Running> = running.iter_mut().filter(|step| step.ended.is_none()).collect();
+  let mut children: Vec<&mut Child> = unfinished.iter_mut().map(|step| &mut step.child).collect();
+  end(&mut children);
+  for step in unfinished {
+    step.ended = Some((outcome, None));
+  }
+}
+
+/// runs the given command without printing anything and provides what it prints to stdout
+pub fn capture_output(run: &str, timeout: Duration) -> Result<String> {
+  let words = shellwords::split(run).map_err(|err| UserError::CannotSplitShellString {
+    source: run.to_owned(),
     err: err.to_string(),
   })?;
-  let ([cmd, ..], args) = words.split_at(1) else {
+  let Some((cmd, args)) = words.split_first() else {
     return Err(UserError::RunCommandIsEmpty);
   };
-  match Command::new(cmd).args(args).status() {
-    Err(_) => Err(UserError::RunCommandNotFound { command: cmd.clone() }),
-    Ok(exit_status) => {
-      if exit_status.success() {
-        Ok(Outcome::TestPass)
-      } else {
-        Ok(Outcome::TestFail)
+  let Ok(mut child) = process::Command::new(cmd)
+    .args(args)
+    .process_group(0)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+  else {
+    return Err(UserError::RunCommandNotFound { command: cmd.clone() });
+  };
+  // read the output while the command runs so that it doesn't block on a full pipe
+  let stdout = Arc::new(Mutex::new(Captured::new(usize::MAX)));
+  let stderr = Arc::new(Mutex::new(Captured::new(usize::MAX)));
+  let readers: Vec<JoinHandle<()>> = [
+    child.stdout.take().map(|source| tee(source, io::sink(), Arc::clone(&stdout))),
+    child.stderr.take().map(|source| tee(source, io::sink(), Arc::clone(&stderr))),
+  ]
+  .into_iter()
+  .flatten()
+  .collect();
+  let start_time = Instant::now();
+  let exit_status = loop {
+    match child.try_wait() {
+      Ok(Some(exit_status)) => break exit_status,
+      Ok(None) => {}
+      Err(err) => return Err(UserError::CannotWaitForCommand { err: err.to_string() }),
+    }
+    if start_time.elapsed() >= timeout {
+      kill(&mut child);
+      return Err(UserError::VarCommandTimeout { command: run.to_owned(), timeout });
+    }
+    thread::sleep(POLL_INTERVAL);
+  };
+  for reader in readers {
+    let _ = reader.join();
+  }
+  let text = |buffer: &Mutex<Captured>| buffer.lock().map(|buffer| buffer.text()).unwrap_or_default();
+  if !exit_status.success() {
+    return Err(UserError::VarCommandFailed {
+      command: run.to_owned(),
+      output: text(&stderr),
+    });
+  }
+  Ok(text(&stdout))
+}
+
+/// copies everything from the given source to the given terminal stream and the given output buffer
+fn tee<R: Read + Send + 'static, W: Write + Send + 'static>(mut source: R, mut terminal: W, output: Arc<Mutex<Captured>>) -> JoinHandle<()> {
+  thread::spawn(move || {
+    let mut buffer = [0; 4096];
+    loop {
+      match source.read(&mut buffer) {
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+        Ok(0) | Err(_) => return,
+        Ok(count) => {
+          let _ = terminal.write_all(&buffer[..count]);
+          let _ = terminal.flush();
+          if let Ok(mut output) = output.lock() {
+            output.add(&buffer[..count]);
+          }
+        }
+      }
+    }
+  })
+}
+
+/// the most recent output of a command, up to a size limit
+struct Captured {
+  bytes: Vec<u8>,
+  /// how many of the most recent bytes to keep
+  limit: usize,
+  /// whether older output got dropped
+  truncated: bool,
+}
+
+impl Captured {
+  fn new(limit: usize) -> Self {
+    Captured {
+      bytes: vec![],
+      limit,
+      truncated: false,
+    }
+  }
+
+  fn add(&mut self, bytes: &[u8]) {
+    self.bytes.extend_from_slice(bytes);
+    // drop old output in batches so that the buffer doesn't get shifted on every read
+    if self.bytes.len() > self.limit.saturating_mul(2) {
+      self.bytes.drain(..self.bytes.len() - self.limit);
+      self.truncated = true;
+    }
+  }
+
+  /// provides the captured output as text, with a note if older output got dropped
+  fn text(&self) -> String {
+    let start = self.bytes.len().saturating_sub(self.limit);
+    let text = String::from_utf8_lossy(&self.bytes[start..]);
+    if self.truncated || start > 0 {
+      format!("[earlier output truncated]\n{text}")
+    } else {
+      text.into_owned()
+    }
+  }
+}
+
+/// writes complete lines to the given terminal stream, with the given prefix in front of each line
+///
+/// Writing whole lines keeps the output of steps that run at the same time from mixing within a line.
+struct PrefixedLines<W: Write> {
+  prefix: String,
+  terminal: W,
+  /// the start of a line whose end hasn't arrived yet
+  partial: Vec<u8>,
+}
+
+impl<W: Write> PrefixedLines<W> {
+  fn new(prefix: String, terminal: W) -> Self {
+    PrefixedLines {
+      prefix,
+      terminal,
+      partial: vec![],
+    }
+  }
+}
+
+impl<W: Write> Write for PrefixedLines<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.partial.extend_from_slice(buf);
+    while let Some(end) = self.partial.iter().position(|byte| *byte == b'\n') {
+      let mut line = self.prefix.as_bytes().to_vec();
+      line.extend(self.partial.drain(..=end));
+      self.terminal.write_all(&line)?;
+    }
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.terminal.flush()
+  }
+}
+
+impl<W: Write> Drop for PrefixedLines<W> {
+  fn drop(&mut self) {
+    if self.partial.is_empty() {
+      return;
+    }
+    let mut line = self.prefix.as_bytes().to_vec();
+    line.append(&mut self.partial);
+    line.push(b'\n');
+    let _ = self.terminal.write_all(&line);
+    let _ = self.terminal.flush();
+  }
+}
+
+/// asks the process groups of the given child processes to shut down, kills the ones that don't do so within the grace period
+fn terminate(children: &mut [&mut Child]) {
+  for child in children.iter() {
+    send_signal(child, nix_signal::Signal::SIGTERM);
+  }
+  let start_time = Instant::now();
+  while start_time.elapsed() < TIMEOUT_GRACE_PERIOD {
+    if children.iter_mut().all(|child| matches!(child.try_wait(), Ok(Some(_)))) {
+      return;
+    }
+    thread::sleep(POLL_INTERVAL);
+  }
+  stop(children);
+}
+
+/// kills the process groups of the given child processes
+fn stop(children: &mut [&mut Child]) {
+  for child in children {
+    kill(child);
+  }
+}
+
+/// kills the process group of the given child process
+fn kill(child: &mut Child) {
+  send_signal(child, nix_signal::Signal::SIGKILL);
+  let _ = child.wait();
+}
+
+fn send_signal(child: &Child, signal: nix_signal::Signal) {
+  if let Ok(pid) = i32::try_from(child.id()) {
+    // the process group might have ended already, nothing to do in this case
+    let _ = killpg(Pid::from_raw(pid), signal);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod captured {
+    use crate::subshell::Captured;
+    use big_s::S;
+
+    #[test]
+    fn within_limit() {
+      let mut captured = Captured::new(10);
+      captured.add(b"one\n");
+      captured.add(b"two\n");
+      assert_eq!(captured.text(), S("one\ntwo\n"));
+    }
+
+    #[test]
+    fn above_limit() {
+      let mut captured = Captured::new(8);
+      captured.add(b"one\n");
+      captured.add(b"two\n");
+      captured.add(b"three\n");
+      assert_eq!(captured.text(), S("[earlier output truncated]\no\nthree\n"));
+    }
+
+    #[test]
+    fn far_above_limit() {
+      let mut captured = Captured::new(4);
+      for _ in 0..10 {
+        captured.add(b"abc\n");
+      }
+      captured.add(b"end\n");
+      assert_eq!(captured.text(), S("[earlier output truncated]\nend\n"));
+    }
+  }
+
+  mod combine {
+    use crate::subshell::{Execution, Outcome, combine};
+    use big_s::S;
+
+    fn execution(outcome: Outcome, exit_code: Option<i32>, output: &str) -> Execution {
+      Execution {
+        outcome,
+        exit_code,
+        output: output.to_owned(),
       }
     }
+
+    #[test]
+    fn all_pass() {
+      let give = vec![execution(Outcome::TestPass, Some(0), "lint ok\n"), execution(Outcome::TestPass, Some(0), "tests ok\n")];
+      let have = combine(&give);
+      assert_eq!(have.outcome, Outcome::TestPass);
+      assert_eq!(have.exit_code, Some(0));
+      assert_eq!(have.output, S("lint ok\ntests ok\n"));
+    }
+
+    #[test]
+    fn first_failure_decides() {
+      let give = vec![
+        execution(Outcome::TestFail, Some(2), "lint failed\n"),
+        execution(Outcome::TestTimeout, None, ""),
+        execution(Outcome::TestPass, Some(0), "tests ok\n"),
+      ];
+      let have = combine(&give);
+      assert_eq!(have.outcome, Outcome::TestFail);
+      assert_eq!(have.exit_code, Some(2));
+    }
+
+    #[test]
+    fn canceled_step_decides() {
+      let give = vec![execution(Outcome::TestFail, Some(1), ""), execution(Outcome::Superseded, None, "")];
+      let have = combine(&give);
+      assert_eq!(have.outcome, Outcome::Superseded);
+      assert_eq!(have.exit_code, None);
+    }
+
+    #[test]
+    fn empty() {
+      let have = combine(&[]);
+      assert_eq!(have.outcome, Outcome::TestPass);
+      assert_eq!(have.exit_code, None);
+    }
+  }
+
+  mod prefixed_lines {
+    use crate::subshell::PrefixedLines;
+    use std::io::Write;
+
+    #[test]
+    fn prefixes_each_line() {
+      let mut terminal = Vec::<u8>::new();
+      let mut writer = PrefixedLines::new("[2] ".to_owned(), &mut terminal);
+      writer.write_all(b"one\ntw").unwrap();
+      writer.write_all(b"o\nthr").unwrap();
+      drop(writer);
+      assert_eq!(String::from_utf8(terminal).unwrap(), "[2] one\n[2] two\n[2] thr\n");
+    }
   }
 }
@@ -0,0 +1,178 @@
+/// separates the elements of placeholders that contain a list of values.
+/// This is the ASCII unit separator, which doesn't occur in the lines of source code that list elements come from.
+pub const LIST_SEPARATOR: &str = "\u{1f}";
+
+/// separates the elements of lists in placeholders without a "join" filter
+pub const DEFAULT_LIST_JOIN: &str = " ";
+
+/// transforms the value of a placeholder, for example `{{ name | trim }}`
+#[derive(Debug, Eq, PartialEq)]
+pub enum Filter {
+  /// quotes the value for use as a single shell word
+  ShellQuote,
+  /// escapes all characters that have a special meaning in regular expressions
+  RegexEscape,
+  /// converts the value to `snake_case`
+  SnakeCase,
+  /// replaces all occurrences of `from` with `to`
+  Replace { from: String, to: String },
+  /// uses the given value if the value is missing or empty
+  Default { value: String },
+  /// removes whitespace at the beginning and end
+  Trim,
+  /// puts the given separator between the elements of a list
+  Join { separator: String },
+}
+
+impl Filter {
+  /// provides the filter with the given name and arguments, or a description of the problem
+  pub fn new(name: &str, args: Vec<String>) -> Result<Filter, String> {
+    let expected_args = match name {
+      "shell_quote" | "regex_escape" | "snake_case" | "trim" => 0,
+      "default" | "join" => 1,
+      "replace" => 2,
+      _ => return Err(format!("unknown filter: {name}")),
+    };
+    if args.len() != expected_args {
+      return Err(match expected_args {
+        0 => format!(r#"filter "{name}" takes no arguments"#),
+        1 => format!(r#"filter "{name}" needs 1 argument"#),
+        _ => format!(r#"filter "{name}" needs {expected_args} arguments"#),
+      });
+    }
+    let mut args = args.into_iter();
+    Ok(match name {
+      "shell_quote" => Filter::ShellQuote,
+      "regex_escape" => Filter::RegexEscape,
+      "snake_case" => Filter::SnakeCase,
+      "trim" => Filter::Trim,
+      "default" => Filter::Default {
+        value: args.next().unwrap_or_default(),
+      },
+      "join" => Filter::Join {
+        separator: args.next().unwrap_or_default(),
+      },
+      _ => Filter::Replace {
+        from: args.next().unwrap_or_default(),
+        to: args.next().unwrap_or_default(),
+      },
+    })
+  }
+
+  /// applies this filter to the given value, which is `None` if the value is not known
+  #[must_use]
+  pub fn apply(&self, value: Option<String>) -> Option<String> {
+    if let Filter::Default { value: default } = self {
+      return match value {
+        Some(value) if !value.is_empty() => Some(value),
+        _ => Some(default.to_owned()),
+      };
+    }
+    let value = value?;
+    Some(match self {
+      Filter::ShellQuote => shell_quote(&value),
+      Filter::RegexEscape => regex::escape(&value),
+      Filter::SnakeCase => snake_case(&value),
+      Filter::Replace { from, to } => value.replace(from.as_str(), to),
+      Filter::Trim => value.trim().to_owned(),
+      Filter::Join { separator } => value.replace(LIST_SEPARATOR, separator),
+      Filter::Default { value: _ } => value,
+    })
+  }
+}
+
+/// quotes the given text so that shells treat it as a single word
+fn shell_quote(text: &str) -> String {
+  let safe = |c: char| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c);
+  if !text.is_empty() && text.chars().all(safe) {
+    return text.to_owned();
+  }
+  format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// converts the given text to `snake_case`
+fn snake_case(text: &str) -> String {
+  let chars: Vec<char> = text.chars().collect();
+  let mut result = String::with_capacity(text.len() + 4);
+  for (index, c) in chars.iter().enumerate() {
+    if !c.is_alphanumeric() {
+      if !result.is_empty() && !result.ends_with('_') {
+        result.push('_');
+      }
+      continue;
+    }
+    if c.is_uppercase() && index > 0 && !result.is_empty() && !result.ends_with('_') {
+      let previous = chars[index - 1];
+      let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+      // start a new word at "aB" and at the "B" in "ABc"
+      if previous.is_lowercase() || previous.is_numeric() || (previous.is_uppercase() && next_is_lower) {
+        result.push('_');
+      }
+    }
+    result.extend(c.to_lowercase());
+  }
+  result.trim_end_matches('_').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod apply {
+    use super::super::{Filter, LIST_SEPARATOR};
+    use big_s::S;
+
+    #[test]
+    fn shell_quote() {
+      assert_eq!(Filter::ShellQuote.apply(Some(S("src/foo.rs"))), Some(S("src/foo.rs")));
+      assert_eq!(Filter::ShellQuote.apply(Some(S("my test"))), Some(S("'my test'")));
+      assert_eq!(Filter::ShellQuote.apply(Some(S("it's"))), Some(S(r"'it'\''s'")));
+      assert_eq!(Filter::ShellQuote.apply(Some(S(""))), Some(S("''")));
+    }
+
+    #[test]
+    fn regex_escape() {
+      assert_eq!(Filter::RegexEscape.apply(Some(S("a.b(c)"))), Some(S(r"a\.b\(c\)")));
+    }
+
+    #[test]
+    fn snake_case() {
+      assert_eq!(Filter::SnakeCase.apply(Some(S("MyTestName"))), Some(S("my_test_name")));
+      assert_eq!(Filter::SnakeCase.apply(Some(S("parseHTTPResponse"))), Some(S("parse_http_response")));
+      assert_eq!(Filter::SnakeCase.apply(Some(S("my-test name"))), Some(S("my_test_name")));
+      assert_eq!(Filter::SnakeCase.apply(Some(S("already_snake"))), Some(S("already_snake")));
+    }
+
+    #[test]
+    fn replace() {
+      let filter = Filter::Replace { from: S("/"), to: S("::") };
+      assert_eq!(filter.apply(Some(S("src/config/file"))), Some(S("src::config::file")));
+    }
+
+    #[test]
+    fn default() {
+      let filter = Filter::Default { value: S("all") };
+      assert_eq!(filter.apply(None), Some(S("all")));
+      assert_eq!(filter.apply(Some(S(""))), Some(S("all")));
+      assert_eq!(filter.apply(Some(S("one"))), Some(S("one")));
+    }
+
+    #[test]
+    fn trim() {
+      assert_eq!(Filter::Trim.apply(Some(S("  one "))), Some(S("one")));
+    }
+
+    #[test]
+    fn join() {
+      let filter = Filter::Join { separator: S(" or ") };
+      assert_eq!(filter.apply(Some(format!("test_one{LIST_SEPARATOR}test_two"))), Some(S("test_one or test_two")));
+      assert_eq!(filter.apply(Some(S("test_one"))), Some(S("test_one")));
+      // values that aren't lists stay as they are, even if they span several lines
+      assert_eq!(filter.apply(Some(S("line one\nline two"))), Some(S("line one\nline two")));
+    }
+
+    #[test]
+    fn unknown_value() {
+      assert_eq!(Filter::Trim.apply(None), None);
+    }
+  }
+}
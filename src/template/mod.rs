@@ -1,9 +1,9 @@
-//! A very simple templating engine that just replaces placeholders in the string template.
+//! A simple templating engine that replaces placeholders like `{{ name | filter }}` in string templates.
 
-mod regex;
-mod replace;
+mod filter;
+mod parse;
 mod replace_all;
 
-pub use regex::regex;
-pub use replace::replace;
+pub use filter::{DEFAULT_LIST_JOIN, Filter, LIST_SEPARATOR};
+pub use parse::{BlockKind, Placeholder, Segment, parse};
 pub use replace_all::replace_all;
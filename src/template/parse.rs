@@ -0,0 +1,462 @@
+use super::Filter;
+use crate::{Result, UserError};
+
+/// a part of a template
+#[derive(Debug, Eq, PartialEq)]
+pub enum Segment {
+  /// text that goes into the result as-is
+  Text(String),
+  /// a placeholder like `{{ name | filter }}`
+  Placeholder(Placeholder),
+  /// a section like `{{#if name}}...{{/if}}` that renders only if its condition holds
+  Block(Block),
+}
+
+/// a placeholder in a template
+#[derive(Debug, Eq, PartialEq)]
+pub struct Placeholder {
+  /// the name of the value to insert
+  pub name: String,
+  /// the filters to apply to the value, in order
+  pub filters: Vec<Filter>,
+  /// the placeholder as written in the template
+  pub source: String,
+}
+
+impl Placeholder {
+  /// indicates whether this placeholder provides a value even if the value it refers to is unknown
+  pub fn has_default(&self) -> bool {
+    self.filters.iter().any(|filter| matches!(filter, Filter::Default { value: _ }))
+  }
+}
+
+/// a conditional section in a template
+#[derive(Debug, Eq, PartialEq)]
+pub struct Block {
+  pub kind: BlockKind,
+  /// the value that decides whether the body renders, it holds if it exists and isn't empty
+  pub condition: Placeholder,
+  pub body: Vec<Segment>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BlockKind {
+  /// renders the body if the condition holds
+  If,
+  /// renders the body if the condition doesn't hold
+  Unless,
+}
+
+impl BlockKind {
+  fn parse(keyword: &str) -> Option<BlockKind> {
+    match keyword {
+      "if" => Some(BlockKind::If),
+      "unless" => Some(BlockKind::Unless),
+      _ => None,
+    }
+  }
+
+  fn keyword(self) -> &'static str {
+    match self {
+      BlockKind::If => "if",
+      BlockKind::Unless => "unless",
+    }
+  }
+}
+
+/// a block whose closing tag hasn't been parsed yet
+struct OpenBlock {
+  kind: BlockKind,
+  condition: Placeholder,
+  /// byte offset of the opening tag in the template
+  start: usize,
+  /// the segments before the opening tag
+  outer: Vec<Segment>,
+}
+
+/// splits the given template into text, placeholders, and blocks
+pub fn parse(template: &str) -> Result<Vec<Segment>> {
+  let mut segments = vec![];
+  let mut open_blocks: Vec<OpenBlock> = vec![];
+  let mut rest = template;
+  let mut offset = 0;
+  while let Some(start) = rest.find("{{") {
+    if let Some(text) = rest[..start].strip_suffix('\\') {
+      // an escaped "\{{" is literal text, for example in commands like `go list -f '\{{.Name}}'`
+      push_text(&mut segments, text);
+      push_text(&mut segments, "{{");
+      offset += start + 2;
+      rest = &rest[start + 2..];
+      continue;
+    }
+    if start > 0 {
+      push_text(&mut segments, &rest[..start]);
+    }
+    let Some(end) = rest[start..].find("}}") else {
+      return Err(syntax_error(template, offset + start, "this placeholder is not closed"));
+    };
+    let tag_start = offset + start;
+    let content = &rest[start + 2..start + end];
+    let trimmed = content.trim_start();
+    let content_start = tag_start + 2 + content.len() - trimmed.len();
+    if let Some(tag) = trimmed.strip_prefix('#') {
+      let keyword_len = tag.find(char::is_whitespace).unwrap_or(tag.len());
+      let Some(kind) = BlockKind::parse(&tag[..keyword_len]) else {
+        return Err(syntax_error(template, content_start, &format!("unknown block: #{}", &tag[..keyword_len])));
+      };
+      let condition = parse_placeholder(template, content_start + 1 + keyword_len, &tag[keyword_len..])?;
+      open_blocks.push(OpenBlock {
+        kind,
+        condition: Placeholder {
+          source: rest[start..start + end + 2].to_owned(),
+          ..condition
+        },
+        start: tag_start,
+        outer: std::mem::take(&mut segments),
+      });
+    } else if let Some(tag) = trimmed.strip_prefix('/') {
+      let Some(open_block) = open_blocks.pop() else {
+        return Err(syntax_error(template, content_start, &format!("no open block to close with /{}", tag.trim())));
+      };
+      if tag.trim() != open_block.kind.keyword() {
+        return Err(syntax_error(template, content_start, &format!("expected /{}", open_block.kind.keyword())));
+      }
+      let body = std::mem::replace(&mut segments, open_block.outer);
+      segments.push(Segment::Block(Block {
+        kind: open_block.kind,
+        condition: open_block.condition,
+        body,
+      }));
+    } else {
+      let placeholder = parse_placeholder(template, tag_start + 2, content)?;
+      segments.push(Segment::Placeholder(Placeholder {
+        source: rest[start..start + end + 2].to_owned(),
+        ..placeholder
+      }));
+    }
+    offset += start + end + 2;
+    rest = &rest[start + end + 2..];
+  }
+  if let Some(open_block) = open_blocks.pop() {
+    return Err(syntax_error(
+      template,
+      open_block.start,
+      &format!("this block is not closed with {{{{/{}}}}}", open_block.kind.keyword()),
+    ));
+  }
+  if !rest.is_empty() {
+    push_text(&mut segments, rest);
+  }
+  Ok(segments)
+}
+
+/// adds the given text to the given segments, merging it into a preceding text segment
+fn push_text(segments: &mut Vec<Segment>, text: &str) {
+  if let Some(Segment::Text(previous)) = segments.last_mut() {
+    previous.push_str(text);
+  } else if !text.is_empty() {
+    segments.push(Segment::Text(text.to_owned()));
+  }
+}
+
+/// parses the content of a placeholder, i.e. the part between `{{` and `}}`,
+/// which starts at the given byte offset in the given template
+fn parse_placeholder(template: &str, offset: usize, content: &str) -> Result<Placeholder> {
+  let mut tokens = tokenize(template, offset, content)?.into_iter().peekable();
+  let Some(Token::Word { text: name, start }) = tokens.next() else {
+    return Err(syntax_error(template, offset, "expected the name of a value"));
+  };
+  if !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+    return Err(syntax_error(template, start, &format!("invalid name: {name}")));
+  }
+  let mut filters = vec![];
+  while let Some(token) = tokens.next() {
+    let Token::Pipe { start: pipe_start } = token else {
+      return Err(syntax_error(template, token.start(), r#"expected "|""#));
+    };
+    let Some(Token::Word {
+      text: filter_name,
+      start: filter_start,
+    }) = tokens.next()
+    else {
+      return Err(syntax_error(template, pipe_start, "expected the name of a filter"));
+    };
+    let mut args = vec![];
+    while let Some(Token::Colon { start: colon_start }) = tokens.peek().cloned() {
+      tokens.next();
+      match tokens.next() {
+        Some(Token::Word { text, start: _ } | Token::Quoted { text, start: _ }) => args.push(text),
+        _ => return Err(syntax_error(template, colon_start, "expected an argument")),
+      }
+    }
+    let filter = Filter::new(&filter_name, args).map_err(|problem| syntax_error(template, filter_start, &problem))?;
+    filters.push(filter);
+  }
+  Ok(Placeholder {
+    name,
+    filters,
+    source: String::new(),
+  })
+}
+
+#[derive(Clone, Debug)]
+enum Token {
+  Word { text: String, start: usize },
+  Quoted { text: String, start: usize },
+  Pipe { start: usize },
+  Colon { start: usize },
+}
+
+impl Token {
+  fn start(&self) -> usize {
+    match self {
+      Token::Word { text: _, start } | Token::Quoted { text: _, start } | Token::Pipe { start } | Token::Colon { start } => *start,
+    }
+  }
+}
+
+/// splits the given placeholder content into tokens
+fn tokenize(template: &str, offset: usize, content: &str) -> Result<Vec<Token>> {
+  let mut tokens = vec![];
+  let mut chars = content.char_indices().peekable();
+  while let Some((index, c)) = chars.next() {
+    let start = offset + index;
+    match c {
+      '|' => tokens.push(Token::Pipe { start }),
+      ':' => tokens.push(Token::Colon { start }),
+      '"' => {
+        let mut text = String::new();
+        loop {
+          match chars.next() {
+            Some((_, '"')) => break,
+            Some((_, '\\')) => match chars.next() {
+              Some((_, escaped)) => text.push(escaped),
+              None => return Err(syntax_error(template, start, "this string is not closed")),
+            },
+            Some((_, c)) => text.push(c),
+            None => return Err(syntax_error(template, start, "this string is not closed")),
+          }
+        }
+        tokens.push(Token::Quoted { text, start });
+      }
+      c if c.is_whitespace() => {}
+      c => {
+        let mut text = String::from(c);
+        while let Some((_, next)) = chars.peek() {
+          if next.is_whitespace() || matches!(next, '|' | ':' | '"') {
+            break;
+          }
+          text.push(*next);
+          chars.next();
+        }
+        tokens.push(Token::Word { text, start });
+      }
+    }
+  }
+  Ok(tokens)
+}
+
+/// provides a syntax error for the given template at the given byte offset
+fn syntax_error(template: &str, offset: usize, problem: &str) -> UserError {
+  UserError::TemplateSyntax {
+    template: template.to_owned(),
+    column: template[..offset].chars().count() + 1,
+    problem: problem.to_owned(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  mod parse {
+    use super::super::{Block, BlockKind, Placeholder, Segment, parse};
+    use crate::UserError;
+    use crate::template::Filter;
+    use big_s::S;
+
+    #[test]
+    fn text_only() {
+      let have = parse("make test").unwrap();
+      assert_eq!(have, vec![Segment::Text(S("make test"))]);
+    }
+
+    #[test]
+    fn placeholders() {
+      let have = parse("cargo test {{ name }}{{file}}").unwrap();
+      let want = vec![
+        Segment::Text(S("cargo test ")),
+        Segment::Placeholder(Placeholder {
+          name: S("name"),
+          filters: vec![],
+          source: S("{{ name }}"),
+        }),
+        Segment::Placeholder(Placeholder {
+          name: S("file"),
+          filters: vec![],
+          source: S("{{file}}"),
+        }),
+      ];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn escaped_braces() {
+      let have = parse(r"docker inspect -f '\{{json .Config}}' {{ name }} \{{x}}").unwrap();
+      let want = vec![
+        Segment::Text(S("docker inspect -f '{{json .Config}}' ")),
+        Segment::Placeholder(Placeholder {
+          name: S("name"),
+          filters: vec![],
+          source: S("{{ name }}"),
+        }),
+        Segment::Text(S(" {{x}}")),
+      ];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn filters() {
+      let have = parse(r#"{{ file | replace:"/":"::" | default:all|trim }}"#).unwrap();
+      let want = vec![Segment::Placeholder(Placeholder {
+        name: S("file"),
+        filters: vec![Filter::Replace { from: S("/"), to: S("::") }, Filter::Default { value: S("all") }, Filter::Trim],
+        source: S(r#"{{ file | replace:"/":"::" | default:all|trim }}"#),
+      })];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn escaped_quote_in_argument() {
+      let have = parse(r#"{{ name | replace:"\"":"'" }}"#).unwrap();
+      let Segment::Placeholder(placeholder) = &have[0] else {
+        unreachable!();
+      };
+      assert_eq!(placeholder.filters, vec![Filter::Replace { from: S("\""), to: S("'") }]);
+    }
+
+    #[test]
+    fn blocks() {
+      let have = parse("test{{#if name}} --exact {{name}}{{/if}}{{#unless file}} --all{{/unless}}").unwrap();
+      let want = vec![
+        Segment::Text(S("test")),
+        Segment::Block(Block {
+          kind: BlockKind::If,
+          condition: Placeholder {
+            name: S("name"),
+            filters: vec![],
+            source: S("{{#if name}}"),
+          },
+          body: vec![
+            Segment::Text(S(" --exact ")),
+            Segment::Placeholder(Placeholder {
+              name: S("name"),
+              filters: vec![],
+              source: S("{{name}}"),
+            }),
+          ],
+        }),
+        Segment::Block(Block {
+          kind: BlockKind::Unless,
+          condition: Placeholder {
+            name: S("file"),
+            filters: vec![],
+            source: S("{{#unless file}}"),
+          },
+          body: vec![Segment::Text(S(" --all"))],
+        }),
+      ];
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn nested_blocks() {
+      let have = parse("{{#if a}}{{#unless b}}x{{/unless}}{{/if}}").unwrap();
+      let Segment::Block(outer) = &have[0] else {
+        unreachable!();
+      };
+      assert_eq!(outer.condition.name, "a");
+      let Segment::Block(inner) = &outer.body[0] else {
+        unreachable!();
+      };
+      assert_eq!(inner.kind, BlockKind::Unless);
+      assert_eq!(inner.body, vec![Segment::Text(S("x"))]);
+    }
+
+    #[test]
+    fn unclosed_block() {
+      let have = parse("test {{#if name}} --exact");
+      let want = Err(UserError::TemplateSyntax {
+        template: S("test {{#if name}} --exact"),
+        column: 6,
+        problem: S("this block is not closed with {{/if}}"),
+      });
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn mismatched_block() {
+      let have = parse("{{#if name}}x{{/unless}}");
+      let want = Err(UserError::TemplateSyntax {
+        template: S("{{#if name}}x{{/unless}}"),
+        column: 16,
+        problem: S("expected /if"),
+      });
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn unknown_block() {
+      let have = parse("{{#each names}}x{{/each}}");
+      let want = Err(UserError::TemplateSyntax {
+        template: S("{{#each names}}x{{/each}}"),
+        column: 3,
+        problem: S("unknown block: #each"),
+      });
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn unknown_filter() {
+      let have = parse("echo {{ name | zonk }}");
+      let want = Err(UserError::TemplateSyntax {
+        template: S("echo {{ name | zonk }}"),
+        column: 16,
+        problem: S("unknown filter: zonk"),
+      });
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn missing_argument() {
+      let have = parse("echo {{ name | default }}");
+      let want = Err(UserError::TemplateSyntax {
+        template: S("echo {{ name | default }}"),
+        column: 16,
+        problem: S(r#"filter "default" needs 1 argument"#),
+      });
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn unclosed_placeholder() {
+      let have = parse("echo {{ name");
+      let want = Err(UserError::TemplateSyntax {
+        template: S("echo {{ name"),
+        column: 6,
+        problem: S("this placeholder is not closed"),
+      });
+      assert_eq!(have, want);
+    }
+
+    #[test]
+    fn missing_name() {
+      let have = parse("echo {{ | trim }}");
+      let want = Err(UserError::TemplateSyntax {
+        template: S("echo {{ | trim }}"),
+        column: 8,
+        problem: S("expected the name of a value"),
+      });
+      assert_eq!(have, want);
+    }
+  }
+}
@@ -1,18 +1,64 @@
-use super::replace;
+use super::{BlockKind, DEFAULT_LIST_JOIN, LIST_SEPARATOR, Placeholder, Segment, parse};
 use crate::Result;
 use ahash::AHashMap;
 
-pub fn replace_all(text: &str, replacements: &AHashMap<&str, String>) -> Result<String> {
-  let mut result = text.to_owned();
-  for (placeholder, replacement) in replacements {
-    result = replace(&result, placeholder, replacement)?;
+/// the result of replacing the placeholders in a template
+#[derive(Debug, Eq, PartialEq)]
+pub struct Rendered {
+  pub text: String,
+  /// names of the placeholders that have no value and therefore remain in the text
+  pub unresolved: Vec<String>,
+}
+
+/// replaces the placeholders in the given template with the given values,
+/// placeholders for unknown values remain as they are
+pub fn replace_all(text: &str, replacements: &AHashMap<&str, String>) -> Result<Rendered> {
+  let mut rendered = Rendered {
+    text: String::with_capacity(text.len()),
+    unresolved: vec![],
+  };
+  render(&parse(text)?, replacements, &mut rendered);
+  Ok(rendered)
+}
+
+/// appends the given segments, rendered with the given values, to the given result
+fn render(segments: &[Segment], replacements: &AHashMap<&str, String>, result: &mut Rendered) {
+  for segment in segments {
+    match segment {
+      Segment::Text(text) => result.text.push_str(text),
+      Segment::Placeholder(placeholder) => {
+        if let Some(value) = value(placeholder, replacements) {
+          result.text.push_str(&value.replace(LIST_SEPARATOR, DEFAULT_LIST_JOIN));
+        } else {
+          result.text.push_str(&placeholder.source);
+          if !result.unresolved.contains(&placeholder.name) {
+            result.unresolved.push(placeholder.name.clone());
+          }
+        }
+      }
+      Segment::Block(block) => {
+        // missing and empty values don't hold
+        let holds = value(&block.condition, replacements).is_some_and(|value| !value.is_empty());
+        if holds == (block.kind == BlockKind::If) {
+          render(&block.body, replacements, result);
+        }
+      }
+    }
   }
-  Ok(result)
+}
+
+/// provides the value of the given placeholder after applying its filters
+fn value(placeholder: &Placeholder, replacements: &AHashMap<&str, String>) -> Option<String> {
+  placeholder
+    .filters
+    .iter()
+    .fold(replacements.get(placeholder.name.as_str()).cloned(), |value, filter| filter.apply(value))
 }
 
 #[cfg(test)]
 mod tests {
-  use super::replace_all;
+  use super::{Rendered, replace_all};
+  use crate::template::LIST_SEPARATOR;
   use ahash::AHashMap;
   use big_s::S;
 
@@ -21,16 +67,30 @@ mod tests {
     let mut replacements = AHashMap::new();
     replacements.insert("foo", S("bar"));
     let give = "a skeleton walks into a {{ foo }}";
-    let have = replace_all(give, &replacements).unwrap();
+    let have = replace_all(give, &replacements).unwrap().text;
     let want = "a skeleton walks into a bar";
     assert_eq!(have, want);
   }
 
+  #[test]
+  fn tight_placeholder() {
+    let replacements = AHashMap::from([("world", S("universe"))]);
+    let have = replace_all("hello {{world}}", &replacements).unwrap().text;
+    assert_eq!(have, "hello universe");
+  }
+
+  #[test]
+  fn multiple_placeholders() {
+    let replacements = AHashMap::from([("hello", S("bye"))]);
+    let have = replace_all("{{ hello }} {{ hello }}", &replacements).unwrap().text;
+    assert_eq!(have, "bye bye");
+  }
+
   #[test]
   fn no_placeholders() {
     let replacements = AHashMap::new();
     let give = "a skeleton walks into a {{ foo }}";
-    let have = replace_all(give, &replacements).unwrap();
+    let have = replace_all(give, &replacements).unwrap().text;
     let want = "a skeleton walks into a {{ foo }}";
     assert_eq!(have, want);
   }
@@ -39,9 +99,63 @@ mod tests {
   fn no_match() {
     let mut replacements = AHashMap::new();
     replacements.insert("foo", S("bar"));
-    let give = "a skeleton walks into a {{ other }}";
+    let give = "a skeleton walks into a {{ other }} and a {{ other }}";
     let have = replace_all(give, &replacements).unwrap();
-    let want = "a skeleton walks into a {{ other }}";
+    let want = Rendered {
+      text: S("a skeleton walks into a {{ other }} and a {{ other }}"),
+      unresolved: vec![S("other")],
+    };
+    assert_eq!(have, want);
+  }
+
+  #[test]
+  fn values_containing_placeholders() {
+    let replacements = AHashMap::from([("one", S("{{ two }}")), ("two", S("2"))]);
+    let have = replace_all("{{ one }}", &replacements).unwrap().text;
+    assert_eq!(have, "{{ two }}");
+  }
+
+  #[test]
+  fn filters() {
+    let replacements = AHashMap::from([("test_name", S("MyTest")), ("file", S("src/foo"))]);
+    let have = replace_all(
+      r#"test {{ test_name | snake_case | shell_quote }} {{ file | replace:"/":"::" }} {{ other | default:"all" }}"#,
+      &replacements,
+    )
+    .unwrap()
+    .text;
+    assert_eq!(have, "test my_test src::foo all");
+  }
+
+  #[test]
+  fn lists() {
+    let replacements = AHashMap::from([("tests", format!("test_one{LIST_SEPARATOR}test_two"))]);
+    let have = replace_all(r#"pytest {{ tests }} -k "{{ tests | join:" or " }}""#, &replacements).unwrap().text;
+    assert_eq!(have, r#"pytest test_one test_two -k "test_one or test_two""#);
+  }
+
+  #[test]
+  fn if_block() {
+    let give = "cargo test{{#if name}} {{ name }} -- --exact{{/if}}";
+    let with_name = AHashMap::from([("name", S("my_test"))]);
+    assert_eq!(replace_all(give, &with_name).unwrap().text, "cargo test my_test -- --exact");
+    let empty_name = AHashMap::from([("name", S(""))]);
+    assert_eq!(replace_all(give, &empty_name).unwrap().text, "cargo test");
+    let without_name = AHashMap::new();
+    let have = replace_all(give, &without_name).unwrap();
+    let want = Rendered {
+      text: S("cargo test"),
+      unresolved: vec![],
+    };
     assert_eq!(have, want);
   }
+
+  #[test]
+  fn unless_block() {
+    let give = "cargo test{{#unless file}} --workspace{{/unless}}";
+    let with_file = AHashMap::from([("file", S("src/lib.rs"))]);
+    assert_eq!(replace_all(give, &with_file).unwrap().text, "cargo test");
+    let without_file = AHashMap::new();
+    assert_eq!(replace_all(give, &without_file).unwrap().text, "cargo test --workspace");
+  }
 }
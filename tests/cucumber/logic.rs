@@ -1,18 +1,30 @@
 use crate::world::{ContestWorld, RunningProcess};
-use contest::client::fifo;
+use contest::client::{fifo, socket};
 use std::os::unix::fs::FileTypeExt;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::fs::{self, File, OpenOptions};
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
 use tokio::process::Command;
 
 pub async fn create_file<AS: AsRef<str>>(path: &Path, content: AS) {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).await.unwrap();
+  }
   let mut file = File::create(path).await.expect("cannot create file {path}");
   file.write_all(content.as_ref().as_bytes()).await.unwrap();
   file.flush().await.unwrap();
 }
 
+/// replaces the content of the given file in the given workspace in a single step, like editors do when saving files
+pub async fn change_file<AS: AsRef<str>>(workspace: &Path, filename: &str, content: AS) {
+  // create the new file outside of the workspace so that creating it doesn't trigger file watchers
+  let temp_file = tempfile::NamedTempFile::new_in(workspace.parent().unwrap()).unwrap();
+  create_file(temp_file.path(), content).await;
+  temp_file.persist(workspace.join(filename)).unwrap();
+}
+
 pub fn fifo_path(workspace: &Path) -> PathBuf {
   workspace.join(fifo::FILE_NAME)
 }
@@ -29,12 +41,23 @@ pub async fn send_command(command: String, workspace: &Path) {
   fifo.write_all(command.as_bytes()).await.unwrap();
 }
 
-pub fn start_contest(world: &mut ContestWorld, args: &[String]) {
+/// sends the given command through the Contest socket, connects to it if necessary
+pub async fn send_socket_command(world: &mut ContestWorld, command: &str) {
+  if world.socket.is_none() {
+    let stream = UnixStream::connect(world.dir.path().join(socket::FILE_NAME)).await.unwrap();
+    world.socket = Some(BufReader::new(stream));
+  }
+  let socket = world.socket.as_mut().unwrap();
+  socket.get_mut().write_all(format!("{command}\n").as_bytes()).await.unwrap();
+}
+
+/// starts Contest in the given subdirectory of the workspace
+pub fn start_contest(world: &mut ContestWorld, args: &[String], subdir: &str) {
   let cwd = std::env::current_dir().unwrap();
   let contest_path = cwd.join("target").join("debug").join("contest");
   let mut cmd = Command::new(contest_path)
     .args(args)
-    .current_dir(world.dir.as_ref())
+    .current_dir(world.dir.as_ref().join(subdir))
     .stdin(Stdio::piped())
     .stdout(Stdio::piped())
     .kill_on_drop(true)
@@ -70,6 +93,13 @@ pub async fn verify_prints_lines(world: &mut ContestWorld, want: &str) {
   }
 }
 
+/// verifies that Contest announces the configuration file it loaded, if the workspace contains one
+pub async fn verify_prints_config_file(world: &mut ContestWorld) {
+  if world.dir.path().join("contest.json").is_file() {
+    verify_prints_lines(world, "using configuration file contest.json").await;
+  }
+}
+
 /// verifies the complete output after the process has finished
 pub async fn verify_prints_text(world: &mut ContestWorld, want: &str) {
   let subprocess = world.subprocess.as_mut().unwrap();
@@ -79,6 +109,26 @@ pub async fn verify_prints_text(world: &mut ContestWorld, want: &str) {
   pretty::assert_eq!(have.trim(), want.trim());
 }
 
+/// verifies that the complete output after the process has finished contains the given text
+pub async fn verify_prints_text_containing(world: &mut ContestWorld, want: &str) {
+  let subprocess = world.subprocess.as_mut().unwrap();
+  let mut have = Vec::<u8>::with_capacity(want.len());
+  subprocess.stdout.read_to_end(&mut have).await.unwrap();
+  let have = String::from_utf8(have).unwrap();
+  assert!(have.contains(want), "output does not contain:\n{want}\n\noutput:\n{have}");
+}
+
+/// verifies the replies that the client receives through the socket
+pub async fn verify_socket_replies(world: &mut ContestWorld, want: &str) {
+  let socket = world.socket.as_mut().unwrap();
+  let mut buffer = String::new();
+  for want_line in want.lines() {
+    buffer.clear();
+    socket.read_line(&mut buffer).await.unwrap();
+    pretty::assert_eq!(buffer.trim(), want_line.trim());
+  }
+}
+
 pub async fn wait_for_exit(world: &mut ContestWorld, code: i32) {
   let subprocess = world.subprocess.as_mut().unwrap();
   let exit_status = subprocess.cmd.wait().await.unwrap();
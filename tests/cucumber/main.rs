@@ -1,6 +1,7 @@
 mod logic;
 mod world;
 
+use big_s::S;
 use cucumber::gherkin::Step;
 use cucumber::{World, given, then, when};
 use world::ContestWorld;
@@ -10,13 +11,27 @@ async fn file_with_content(world: &mut ContestWorld, step: &Step, filename: Stri
   logic::create_file(&world.dir.as_ref().join(filename), step.docstring.as_ref().expect("no docstring")).await;
 }
 
+#[when(expr = "file {string} changes to")]
+async fn file_changes(world: &mut ContestWorld, step: &Step, filename: String) {
+  logic::change_file(world.dir.path(), &filename, step.docstring.as_ref().expect("no docstring")).await;
+}
+
 #[when(expr = "I run {string}")]
 #[allow(clippy::needless_pass_by_value)]
 fn start_contest(world: &mut ContestWorld, command: String) {
   let words = shellwords::split(&command).unwrap();
   let (cmd, args) = words.split_at(1);
   assert!(cmd == ["contest"], "can only execute contest");
-  logic::start_contest(world, args);
+  logic::start_contest(world, args, "");
+}
+
+#[when(expr = "I run {string} in {string}")]
+#[allow(clippy::needless_pass_by_value)]
+fn start_contest_in_subdir(world: &mut ContestWorld, command: String, subdir: String) {
+  let words = shellwords::split(&command).unwrap();
+  let (cmd, args) = words.split_at(1);
+  assert!(cmd == ["contest"], "can only execute contest");
+  logic::start_contest(world, args, &subdir);
 }
 
 #[then("it prints")]
@@ -43,6 +58,12 @@ async fn it_exits_with_output(world: &mut ContestWorld, step: &Step) {
   logic::wait_for_exit(world, 0).await;
 }
 
+#[then("it exits with output containing")]
+async fn it_exits_with_output_containing(world: &mut ContestWorld, step: &Step) {
+  logic::verify_prints_text_containing(world, step.docstring.as_ref().unwrap().trim()).await;
+  logic::wait_for_exit(world, 0).await;
+}
+
 #[then("it fails with this output")]
 async fn it_fails_with_output(world: &mut ContestWorld, step: &Step) {
   logic::verify_prints_text(world, step.docstring.as_ref().unwrap().trim()).await;
@@ -54,6 +75,16 @@ async fn client_sends_command(world: &mut ContestWorld, command: String) {
   logic::send_command(command, world.dir.as_ref()).await;
 }
 
+#[when(expr = "a client sends {string} through the socket")]
+async fn client_sends_through_socket(world: &mut ContestWorld, command: String) {
+  logic::send_socket_command(world, &command).await;
+}
+
+#[then("the client receives")]
+async fn client_receives(world: &mut ContestWorld, step: &Step) {
+  logic::verify_socket_replies(world, step.docstring.as_ref().unwrap().trim()).await;
+}
+
 #[then(expr = "the server stops running")]
 async fn server_no_longer_running(world: &mut ContestWorld) {
   let subprocess = world.subprocess.as_mut().unwrap();
@@ -61,9 +92,17 @@ async fn server_no_longer_running(world: &mut ContestWorld) {
   assert!(exit_status.success());
 }
 
+#[given(expr = "Contest is watching")]
+async fn contest_is_watching(world: &mut ContestWorld) {
+  logic::start_contest(world, &[S("watch")], "");
+  logic::verify_prints_config_file(world).await;
+  logic::verify_prints_lines(world, "Contest is online and watching for file changes, Ctrl-C to exit").await;
+}
+
 #[given(expr = "Contest is running")]
 async fn contest_is_running(world: &mut ContestWorld) {
-  logic::start_contest(world, &[]);
+  logic::start_contest(world, &[], "");
+  logic::verify_prints_config_file(world).await;
   logic::verify_prints_lines(world, "Contest is online, Ctrl-C to exit").await;
 }
 
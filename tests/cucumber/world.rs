@@ -1,5 +1,7 @@
 use cucumber::World;
 use tempfile::TempDir;
+use tokio::io::BufReader;
+use tokio::net::UnixStream;
 use tokio::process::ChildStdout;
 
 #[derive(Debug, World)]
@@ -9,12 +11,14 @@ pub struct ContestWorld {
   pub subprocess: Option<RunningProcess>,
   /// the directory containing the source code that Contest should check
   pub dir: TempDir,
+  /// the connection of a client to the Contest socket
+  pub socket: Option<BufReader<UnixStream>>,
 }
 
 #[derive(Debug)]
 pub struct RunningProcess {
   pub cmd: tokio::process::Child,
-  pub stdout: tokio::io::BufReader<ChildStdout>,
+  pub stdout: BufReader<ChildStdout>,
 }
 
 impl ContestWorld {
@@ -22,6 +26,7 @@ impl ContestWorld {
     Self {
       dir: tempfile::tempdir().unwrap(),
       subprocess: None,
+      socket: None,
     }
   }
 }